@@ -0,0 +1,28 @@
+search = Search
+no-results = No applications found
+add-favorite = Add to Favorites
+remove-favorite = Remove from Favorites
+hide = Hide
+move-up = Move Up
+move-down = Move Down
+apps-menu = Apps Menu
+launch-failed = Failed to launch {$name}
+
+category-favorites = Favorites
+category-recent = Recent
+category-frequent = Frequent
+category-other = Other
+category-audio-video = Multimedia
+category-audio = Audio
+category-video = Video
+category-development = Development
+category-education = Education
+category-game = Games
+category-graphics = Graphics
+category-network = Internet
+category-office = Office
+category-science = Science
+category-settings = Settings
+category-system = System
+category-utility = Accessories
+category-cosmic = COSMIC
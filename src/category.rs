@@ -0,0 +1,205 @@
+//! Freedesktop categories and the pseudo-categories of the menu.
+//!
+//! <https://specifications.freedesktop.org/menu-spec/latest/category-registry.html>
+
+use crate::fl;
+
+/// Additional categories of the registry with the main categories they are related to.
+const ADDITIONAL_CATEGORIES: &[(&str, &[&str])] = &[
+    ("Building", &["Development"]),
+    ("Debugger", &["Development"]),
+    ("IDE", &["Development"]),
+    ("GUIDesigner", &["Development"]),
+    ("Profiling", &["Development"]),
+    ("RevisionControl", &["Development"]),
+    ("Translation", &["Development"]),
+    ("Calendar", &["Office"]),
+    ("ContactManagement", &["Office"]),
+    ("Database", &["Office", "Development", "AudioVideo"]),
+    ("Dictionary", &["Office", "Utility"]),
+    ("Chart", &["Office"]),
+    ("Email", &["Office", "Network"]),
+    ("Finance", &["Office"]),
+    ("FlowChart", &["Office"]),
+    ("PDA", &["Office"]),
+    ("ProjectManagement", &["Office", "Development"]),
+    ("Presentation", &["Office"]),
+    ("Spreadsheet", &["Office"]),
+    ("WordProcessor", &["Office"]),
+    ("2DGraphics", &["Graphics"]),
+    ("VectorGraphics", &["Graphics"]),
+    ("RasterGraphics", &["Graphics"]),
+    ("3DGraphics", &["Graphics"]),
+    ("Scanning", &["Graphics"]),
+    ("OCR", &["Graphics"]),
+    ("Photography", &["Graphics", "Office"]),
+    ("Publishing", &["Graphics", "Office"]),
+    ("Viewer", &["Graphics", "Office"]),
+    ("TextTools", &["Utility"]),
+    ("DesktopSettings", &["Settings"]),
+    ("HardwareSettings", &["Settings"]),
+    ("Printing", &["Settings"]),
+    ("PackageManager", &["Settings"]),
+    ("Dialup", &["Network"]),
+    ("InstantMessaging", &["Network"]),
+    ("Chat", &["Network"]),
+    ("IRCClient", &["Network"]),
+    ("Feed", &["Network"]),
+    ("FileTransfer", &["Network"]),
+    ("HamRadio", &["Network", "Audio"]),
+    ("News", &["Network"]),
+    ("P2P", &["Network"]),
+    ("RemoteAccess", &["Network"]),
+    ("Telephony", &["Network"]),
+    ("TelephonyTools", &["Utility"]),
+    ("VideoConference", &["Network"]),
+    ("WebBrowser", &["Network"]),
+    ("WebDevelopment", &["Network", "Development"]),
+    ("Midi", &["AudioVideo", "Audio"]),
+    ("Mixer", &["AudioVideo", "Audio"]),
+    ("Sequencer", &["AudioVideo", "Audio"]),
+    ("Tuner", &["AudioVideo", "Audio"]),
+    ("TV", &["AudioVideo", "Video"]),
+    ("AudioVideoEditing", &["Audio", "Video", "AudioVideo"]),
+    ("Player", &["Audio", "Video", "AudioVideo"]),
+    ("Recorder", &["Audio", "Video", "AudioVideo"]),
+    ("DiscBurning", &["AudioVideo"]),
+    ("ActionGame", &["Game"]),
+    ("AdventureGame", &["Game"]),
+    ("ArcadeGame", &["Game"]),
+    ("BoardGame", &["Game"]),
+    ("BlocksGame", &["Game"]),
+    ("CardGame", &["Game"]),
+    ("KidsGame", &["Game"]),
+    ("LogicGame", &["Game"]),
+    ("RolePlaying", &["Game"]),
+    ("Shooter", &["Game"]),
+    ("Simulation", &["Game"]),
+    ("SportsGame", &["Game"]),
+    ("StrategyGame", &["Game"]),
+    ("Art", &["Education"]),
+    ("Construction", &["Education"]),
+    ("Music", &["AudioVideo", "Education"]),
+    ("Languages", &["Education"]),
+    ("ArtificialIntelligence", &["Education", "Science"]),
+    ("Astronomy", &["Education", "Science"]),
+    ("Biology", &["Education", "Science"]),
+    ("Chemistry", &["Education", "Science"]),
+    ("ComputerScience", &["Education", "Science"]),
+    ("DataVisualization", &["Education", "Science"]),
+    ("Economy", &["Education"]),
+    ("Electricity", &["Education", "Science"]),
+    ("Geography", &["Education"]),
+    ("Geology", &["Education", "Science"]),
+    ("Geoscience", &["Education", "Science"]),
+    ("History", &["Education"]),
+    ("Humanities", &["Education", "Science"]),
+    ("ImageProcessing", &["Education", "Science"]),
+    ("Literature", &["Education"]),
+    ("Maps", &["Education", "Science", "Utility"]),
+    ("Math", &["Education", "Science"]),
+    ("NumericalAnalysis", &["Education", "Science"]),
+    ("MedicalSoftware", &["Education", "Science"]),
+    ("Physics", &["Education", "Science"]),
+    ("Robotics", &["Education", "Science"]),
+    ("Spirituality", &["Education", "Science", "Utility"]),
+    ("Sports", &["Education"]),
+    ("ParallelComputing", &["Education", "Science"]),
+    ("Archiving", &["Utility"]),
+    ("Compression", &["Utility"]),
+    ("Emulator", &["System", "Game"]),
+    ("FileTools", &["Utility", "System"]),
+    ("FileManager", &["System"]),
+    ("TerminalEmulator", &["System"]),
+    ("Filesystem", &["System"]),
+    ("Monitor", &["System"]),
+    ("Security", &["Settings", "System"]),
+    ("Accessibility", &["Settings", "Utility"]),
+    ("Calculator", &["Utility"]),
+    ("Clock", &["Utility"]),
+    ("TextEditor", &["Utility"]),
+];
+
+/// The main categories an additional category is related to, empty for any other category.
+pub fn main_categories(category: &str) -> &'static [&'static str] {
+    ADDITIONAL_CATEGORIES
+        .iter()
+        .find(|(additional, _)| additional.eq_ignore_ascii_case(category))
+        .map_or(&[], |(_, main)| main)
+}
+
+/// The name of the sub-category `main/sub`, if `category` is one.
+pub fn sub_category(category: &str) -> Option<&str> {
+    category.split_once('/').map(|(_, sub)| sub)
+}
+
+/// The translated name of `category`, unknown categories are shown as they are.
+///
+/// Only the label is translated, the identifiers in the config stay the same.
+/// Sub-categories are named after the additional category, split into words.
+pub fn display_name(category: &str) -> String {
+    if let Some(sub) = sub_category(category) {
+        return split_words(sub);
+    }
+    match category.to_ascii_lowercase().as_str() {
+        "favorites" => fl!("category-favorites"),
+        "recent" => fl!("category-recent"),
+        "frequent" => fl!("category-frequent"),
+        "other" => fl!("category-other"),
+        "audiovideo" => fl!("category-audio-video"),
+        "audio" => fl!("category-audio"),
+        "video" => fl!("category-video"),
+        "development" => fl!("category-development"),
+        "education" => fl!("category-education"),
+        "game" => fl!("category-game"),
+        "graphics" => fl!("category-graphics"),
+        "network" => fl!("category-network"),
+        "office" => fl!("category-office"),
+        "science" => fl!("category-science"),
+        "settings" => fl!("category-settings"),
+        "system" => fl!("category-system"),
+        "utility" => fl!("category-utility"),
+        "cosmic" => fl!("category-cosmic"),
+        _ => category.to_string(),
+    }
+}
+
+/// The themed icon of `category`, following the freedesktop icon naming specification.
+pub fn icon_name(category: &str) -> Option<&'static str> {
+    let icon = match category.to_ascii_lowercase().as_str() {
+        "favorites" => "starred",
+        "recent" => "document-open-recent",
+        "frequent" => "view-sort-descending",
+        "other" => "applications-other",
+        "audiovideo" | "audio" | "video" => "applications-multimedia",
+        "development" => "applications-development",
+        "education" | "science" => "applications-science",
+        "game" => "applications-games",
+        "graphics" => "applications-graphics",
+        "network" => "applications-internet",
+        "office" => "applications-office",
+        "settings" => "preferences-system",
+        "system" => "applications-system",
+        "utility" => "applications-utilities",
+        "cosmic" => "preferences-desktop",
+        _ => return None,
+    };
+    Some(icon)
+}
+
+/// Inserts spaces into a CamelCase identifier, e.g. `GUIDesigner` becomes `GUI Designer`.
+fn split_words(identifier: &str) -> String {
+    let chars: Vec<char> = identifier.chars().collect();
+    let mut words = String::with_capacity(identifier.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if i > 0 && c.is_uppercase() {
+            let prev = chars[i - 1];
+            let next_lowercase = chars.get(i + 1).map_or(false, |next| next.is_lowercase());
+            if prev.is_lowercase() || (prev.is_uppercase() && next_lowercase) {
+                words.push(' ');
+            }
+        }
+        words.push(c);
+    }
+    words
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::vec;
 
 use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
@@ -5,11 +6,147 @@ use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, Cosmi
 use serde::{Deserialize, Serialize};
 pub const CONFIG_VERSION: u64 = 1;
 
+/// The config of the COSMIC dock, which owns the favorites.
+pub const APP_LIST_ID: &str = "com.system76.CosmicAppList";
+pub const APP_LIST_CONFIG_VERSION: u64 = 1;
+
 #[derive(Clone, CosmicConfigEntry, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Config {
     pub skip_empty_categories: bool,
     pub categories: Vec<String>,
     pub sort_categories: bool,
+    /// Expand additional categories like `ArcadeGame` below their main category.
+    pub sub_categories: bool,
+    /// Command prefix used for `Terminal=true` entries, autodetected if `None`.
+    pub terminal: Option<String>,
+    /// List entries regardless of `NoDisplay`, `Hidden`, `OnlyShowIn`, `NotShowIn` and `TryExec`.
+    pub show_hidden: bool,
+    /// Show the most recently launched apps in a `Recent` category.
+    pub show_recent: bool,
+    /// Show the most frequently launched apps in a `Frequent` category.
+    pub show_frequent: bool,
+    /// How many apps the launch history keeps for `Recent` and `Frequent`.
+    pub history_size: usize,
+    pub launch_mode: LaunchMode,
+    /// Icon names by category, `None` hides the icon of a category.
+    pub category_icons: HashMap<String, Option<String>>,
+    /// Group entries by the system's `applications.menu` instead of `categories`.
+    pub xdg_menu: bool,
+    /// Categories defined here rather than in desktop files, by name.
+    pub custom_categories: HashMap<String, CustomCategory>,
+    /// Changes to single entries by appid, applied to what the desktop file says.
+    pub overrides: HashMap<String, Override>,
+    /// The order of entries in categories without their own sort mode.
+    pub sort_mode: SortMode,
+    pub category_sort_modes: HashMap<String, SortMode>,
+    /// Appids by category for [`SortMode::Manual`].
+    pub manual_order: HashMap<String, Vec<String>>,
+    /// How entries are shown in categories without their own layout and in search results.
+    pub entry_layout: EntryLayout,
+    pub category_layouts: HashMap<String, EntryLayout>,
+    pub grid_columns: usize,
+    /// Icon size of [`EntryLayout::Grid`] in pixels.
+    pub grid_icon_size: u16,
+    /// Wrap names in the grid onto a second line instead of shortening them.
+    pub wrap_labels: bool,
+    pub entry_details: EntryDetails,
+    /// Width of the popup in pixels, it never exceeds the space the panel leaves on the output.
+    pub popup_width: u32,
+    /// Maximum height of the popup in pixels, limited the same way.
+    ///
+    /// `None` uses a share of the height of the output.
+    pub popup_height: Option<u32>,
+    /// Maximum number of entry rows shown without scrolling, `None` fills the popup height.
+    pub max_visible_rows: Option<u32>,
+}
+
+/// How the `GenericName` and `Comment` of entries are shown.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum EntryDetails {
+    Hidden,
+    /// A dimmed line below the name in the list, the grid has no room and uses tooltips.
+    Subtitle,
+    #[default]
+    Tooltip,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum EntryLayout {
+    /// A row with a small icon per entry.
+    #[default]
+    List,
+    /// Large icons with the name below, `grid_columns` per row.
+    Grid,
+}
+
+/// How the entries of a category are ordered, ties are broken alphabetically.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum SortMode {
+    #[default]
+    Alphabetical,
+    /// The order of the favorites in the dock, other entries come last.
+    Favorites,
+    /// Highest frecency in the launch history first.
+    MostUsed,
+    /// Most recently modified desktop files first.
+    RecentlyInstalled,
+    /// The order of `manual_order`, unlisted entries come last.
+    Manual,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct Override {
+    /// Hides the entry unless `show_hidden` is set.
+    pub hide: bool,
+    pub name: Option<String>,
+    pub icon: Option<String>,
+    /// Replaces the `Categories` key.
+    pub categories: Option<Vec<String>>,
+}
+
+/// The entries of a custom category, an entry has to match any of the fields.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct CustomCategory {
+    pub appids: Vec<String>,
+    pub rules: Vec<Rule>,
+    /// Existing categories whose entries are included, custom categories are not.
+    pub categories: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Rule {
+    pub field: Field,
+    pub pattern: Pattern,
+}
+
+/// The part of an entry a [`Rule`] matches.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Field {
+    Appid,
+    /// The translated name.
+    Name,
+    /// The parsed `Exec` key, with its arguments separated by spaces.
+    Exec,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Pattern {
+    /// Matches the whole field, `*` stands for any number of characters and `?` for one.
+    Glob(String),
+    /// Matches anywhere in the field unless anchored.
+    Regex(String),
+}
+
+/// How launched apps relate to the applet process.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub enum LaunchMode {
+    /// Apps stay children of the applet and share its cgroup.
+    #[default]
+    Child,
+    /// Every app is moved into its own transient systemd user scope.
+    Scope,
 }
 
 impl Default for Config {
@@ -21,6 +158,7 @@ impl Default for Config {
                 "Audio".into(),
                 "AudioVideo".into(),
                 "COSMIC".into(),
+                "Development".into(),
                 "Education".into(),
                 "Game".into(),
                 "Graphics".into(),
@@ -33,6 +171,29 @@ impl Default for Config {
                 "Other".into(),
             ],
             sort_categories: true,
+            sub_categories: true,
+            terminal: None,
+            show_hidden: false,
+            show_recent: false,
+            show_frequent: true,
+            history_size: 10,
+            launch_mode: LaunchMode::default(),
+            category_icons: HashMap::new(),
+            xdg_menu: false,
+            custom_categories: HashMap::new(),
+            overrides: HashMap::new(),
+            sort_mode: SortMode::default(),
+            category_sort_modes: HashMap::from([("Favorites".into(), SortMode::Favorites)]),
+            manual_order: HashMap::new(),
+            entry_layout: EntryLayout::default(),
+            category_layouts: HashMap::new(),
+            grid_columns: 4,
+            grid_icon_size: 48,
+            wrap_labels: true,
+            entry_details: EntryDetails::default(),
+            popup_width: 500,
+            popup_height: None,
+            max_visible_rows: None,
         }
     }
 }
@@ -0,0 +1,153 @@
+//! Matching of entries against the rules of a [`CustomCategory`].
+
+use regex::Regex;
+
+use crate::config::{CustomCategory, Field, Pattern};
+
+/// A custom category with its patterns compiled.
+pub struct Matcher {
+    appids: Vec<String>,
+    rules: Vec<(Field, CompiledPattern)>,
+}
+
+enum CompiledPattern {
+    Glob(String),
+    Regex(Regex),
+}
+
+impl Matcher {
+    /// Invalid regular expressions are logged and never match.
+    pub fn new(name: &str, category: &CustomCategory) -> Self {
+        let rules = category
+            .rules
+            .iter()
+            .filter_map(|rule| {
+                let pattern = match &rule.pattern {
+                    Pattern::Glob(glob) => CompiledPattern::Glob(glob.clone()),
+                    Pattern::Regex(regex) => match Regex::new(regex) {
+                        Ok(regex) => CompiledPattern::Regex(regex),
+                        Err(err) => {
+                            eprintln!("invalid regex in custom category {}: {}", name, err);
+                            return None;
+                        }
+                    },
+                };
+                Some((rule.field, pattern))
+            })
+            .collect();
+        Matcher {
+            appids: category.appids.clone(),
+            rules,
+        }
+    }
+
+    /// Whether the appid is listed or any rule matches, `exec` is the joined `Exec` key.
+    pub fn matches(&self, appid: &str, name: &str, exec: &str) -> bool {
+        if self.appids.iter().any(|listed| listed == appid) {
+            return true;
+        }
+        self.rules.iter().any(|(field, pattern)| {
+            let value = match field {
+                Field::Appid => appid,
+                Field::Name => name,
+                Field::Exec => exec,
+            };
+            match pattern {
+                CompiledPattern::Glob(glob) => glob_match(glob, value),
+                CompiledPattern::Regex(regex) => regex.is_match(value),
+            }
+        })
+    }
+}
+
+/// Matches all of `text` against a shell-style `pattern`.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // the pattern position after the last `*` and the text position it was tried at
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                p += 1;
+                backtrack = Some((p, t));
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            // let the last `*` consume one more character
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Rule;
+
+    fn rule_matcher(field: Field, pattern: Pattern) -> Matcher {
+        let category = CustomCategory {
+            rules: vec![Rule { field, pattern }],
+            ..Default::default()
+        };
+        Matcher::new("test", &category)
+    }
+
+    #[test]
+    fn glob_wildcards() {
+        assert!(glob_match("org.gnome.*", "org.gnome.Nautilus"));
+        assert!(glob_match("org.gnome.*", "org.gnome."));
+        assert!(!glob_match("org.gnome.*", "org.kde.dolphin"));
+        assert!(glob_match("f?refox", "firefox"));
+        assert!(!glob_match("f?refox", "frefox"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("?", ""));
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "a"));
+    }
+
+    #[test]
+    fn glob_backtracking() {
+        assert!(glob_match("a*b", "acb"));
+        assert!(glob_match("a*b", "abcb"));
+        assert!(!glob_match("a*b", "abc"));
+        assert!(glob_match("*a*b*", "xaybz"));
+        assert!(glob_match("a*?b", "acdb"));
+        assert!(!glob_match("a*?b", "ab"));
+    }
+
+    #[test]
+    fn matches_fields() {
+        let category = CustomCategory {
+            appids: vec!["org.example.Listed".to_string()],
+            ..Default::default()
+        };
+        assert!(Matcher::new("test", &category).matches("org.example.Listed", "", ""));
+
+        let matcher = rule_matcher(Field::Exec, Pattern::Glob("steam *".to_string()));
+        assert!(matcher.matches("game", "Game", "steam steam://rungameid/1"));
+        assert!(!matcher.matches("steam", "steam", "steamcmd"));
+
+        let matcher = rule_matcher(Field::Name, Pattern::Regex("(?i)office".to_string()));
+        assert!(matcher.matches("writer", "LibreOffice Writer", "soffice"));
+        assert!(!matcher.matches("office", "Writer", "office"));
+    }
+
+    #[test]
+    fn invalid_regex_never_matches() {
+        let matcher = rule_matcher(Field::Appid, Pattern::Regex("(".to_string()));
+        assert!(!matcher.matches("(", "(", "("));
+        assert!(!matcher.matches("", "", ""));
+    }
+}
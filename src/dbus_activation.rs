@@ -0,0 +1,198 @@
+//! Launching of `DBusActivatable=true` entries over the `org.freedesktop.Application` interface.
+//!
+//! <https://specifications.freedesktop.org/desktop-entry-spec/latest/dbus.html>
+
+use std::collections::HashMap;
+
+use zbus::zvariant::Value;
+use zbus::Connection;
+
+#[zbus::proxy(interface = "org.freedesktop.Application")]
+trait Application {
+    fn activate(&self, platform_data: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+
+    fn activate_action(
+        &self,
+        action_name: &str,
+        parameter: &[Value<'_>],
+        platform_data: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<()>;
+}
+
+/// Activates `appid`, or its `action`, on the session bus.
+pub async fn activate(appid: &str, action: Option<&str>, token: Option<&str>) -> zbus::Result<()> {
+    let connection = Connection::session().await?;
+    activate_on(&connection, appid, action, token).await
+}
+
+/// Activates `appid`, or its `action`, on `connection`.
+///
+/// The bus name is the desktop file ID without the `.desktop` suffix, which is
+/// started by the bus if the application is not running yet.
+pub async fn activate_on(
+    connection: &Connection,
+    appid: &str,
+    action: Option<&str>,
+    token: Option<&str>,
+) -> zbus::Result<()> {
+    let path = format!("/{}", appid.replace('.', "/").replace('-', "_"));
+    let proxy = ApplicationProxy::builder(connection)
+        .destination(appid)?
+        .path(path)?
+        .build()
+        .await?;
+
+    let mut platform_data = HashMap::new();
+    if let Some(token) = token {
+        platform_data.insert("activation-token", Value::from(token));
+        platform_data.insert("desktop-startup-id", Value::from(token));
+    }
+    match action {
+        Some(action) => proxy.activate_action(action, &[], platform_data).await,
+        None => proxy.activate(platform_data).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Child, Command, Stdio};
+    use std::sync::{Arc, Mutex};
+
+    use zbus::message::Header;
+    use zbus::zvariant::OwnedValue;
+
+    use super::*;
+
+    const APPID: &str = "org.example.Mock-App";
+    /// The object path [`activate_on`] derives from [`APPID`].
+    const PATH: &str = "/org/example/Mock_App";
+
+    /// The calls received by [`MockApplication`], with their activation token and object path.
+    type Calls = Arc<Mutex<Vec<(String, Option<String>, String)>>>;
+
+    struct MockApplication {
+        calls: Calls,
+    }
+
+    impl MockApplication {
+        fn record(
+            &self,
+            method: &str,
+            platform_data: &HashMap<String, OwnedValue>,
+            header: &Header<'_>,
+        ) {
+            let token = platform_data
+                .get("activation-token")
+                .and_then(|token| <&str>::try_from(&**token).ok())
+                .map(str::to_string);
+            let path = header
+                .path()
+                .map(|path| path.to_string())
+                .unwrap_or_default();
+            let call = (method.to_string(), token, path);
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Application")]
+    impl MockApplication {
+        fn activate(
+            &self,
+            platform_data: HashMap<String, OwnedValue>,
+            #[zbus(header)] header: Header<'_>,
+        ) {
+            self.record("Activate", &platform_data, &header);
+        }
+
+        fn activate_action(
+            &self,
+            action_name: String,
+            _parameter: Vec<OwnedValue>,
+            platform_data: HashMap<String, OwnedValue>,
+            #[zbus(header)] header: Header<'_>,
+        ) {
+            let method = format!("ActivateAction {action_name}");
+            self.record(&method, &platform_data, &header);
+        }
+    }
+
+    /// A private session bus, killed when dropped.
+    struct DBusDaemon(Child);
+
+    impl DBusDaemon {
+        fn start() -> (Self, String) {
+            let mut child = Command::new("dbus-daemon")
+                .args(["--session", "--nofork", "--print-address"])
+                .stdout(Stdio::piped())
+                .spawn()
+                .unwrap();
+            let mut address = String::new();
+            BufReader::new(child.stdout.take().unwrap())
+                .read_line(&mut address)
+                .unwrap();
+            (DBusDaemon(child), address.trim().to_string())
+        }
+    }
+
+    impl Drop for DBusDaemon {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    #[test]
+    #[ignore = "needs dbus-daemon"]
+    fn activates_mock_application() {
+        let (_daemon, address) = DBusDaemon::start();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let calls = runtime.block_on(async {
+            let calls = Calls::default();
+            let application = MockApplication {
+                calls: calls.clone(),
+            };
+            let _service = zbus::connection::Builder::address(address.as_str())
+                .unwrap()
+                .name(APPID)
+                .unwrap()
+                .serve_at(PATH, application)
+                .unwrap()
+                .build()
+                .await
+                .unwrap();
+
+            let client = zbus::connection::Builder::address(address.as_str())
+                .unwrap()
+                .build()
+                .await
+                .unwrap();
+            activate_on(&client, APPID, None, Some("token-1"))
+                .await
+                .unwrap();
+            activate_on(&client, APPID, Some("new-window"), Some("token-2"))
+                .await
+                .unwrap();
+            activate_on(&client, APPID, None, None).await.unwrap();
+            let calls = calls.lock().unwrap().clone();
+            calls
+        });
+
+        let path = PATH.to_string();
+        assert_eq!(
+            calls,
+            [
+                ("Activate".into(), Some("token-1".into()), path.clone()),
+                (
+                    "ActivateAction new-window".into(),
+                    Some("token-2".into()),
+                    path.clone()
+                ),
+                ("Activate".into(), None, path),
+            ]
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! Dragging entries out of the popup, e.g. onto the dock or into a file manager.
+
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+use cosmic::iced::wayland::actions::data_device::DataFromMimeType;
+
+pub const URI_LIST: &str = "text/uri-list";
+
+/// A desktop file offered as [`URI_LIST`].
+pub struct DesktopFile(pub PathBuf);
+
+impl DataFromMimeType for DesktopFile {
+    fn from_mime_type(&self, mime_type: &str) -> Option<Vec<u8>> {
+        (mime_type == URI_LIST).then(|| format!("{}\r\n", file_uri(&self.0)).into_bytes())
+    }
+}
+
+/// The `file://` URI of an absolute path, with reserved bytes percent-encoded.
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for &byte in path.as_os_str().as_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
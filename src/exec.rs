@@ -0,0 +1,239 @@
+//! Parsing of the `Exec` key as described in the Desktop Entry Specification.
+//!
+//! <https://specifications.freedesktop.org/desktop-entry-spec/latest/exec-variables.html>
+
+use std::fmt;
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+
+/// Values the field codes of an `Exec` key expand to.
+#[derive(Clone, Copy, Debug)]
+pub struct FieldCodes<'a> {
+    /// The `Icon` key, `%i` is removed if there is none.
+    pub icon: Option<&'a str>,
+    /// The translated `Name` key for `%c`.
+    pub name: &'a str,
+    /// The location of the desktop file for `%k`.
+    pub path: &'a Path,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// There is no program to run.
+    Empty,
+    /// A quoted argument is missing its closing quote.
+    UnterminatedQuote,
+    /// A backslash escapes a character that can not be escaped.
+    InvalidEscape(char),
+    /// A `%` is the last character.
+    UnfinishedFieldCode,
+    /// A field code which is not defined by the specification.
+    InvalidFieldCode(char),
+    /// A field code which has to be an argument on its own, but is not.
+    MisplacedFieldCode(char),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Empty => write!(f, "no program to execute"),
+            Error::UnterminatedQuote => write!(f, "unterminated quoted argument"),
+            Error::InvalidEscape(c) => write!(f, "invalid escape sequence \\{c}"),
+            Error::UnfinishedFieldCode => write!(f, "unfinished field code"),
+            Error::InvalidFieldCode(c) => write!(f, "invalid field code %{c}"),
+            Error::MisplacedFieldCode(c) => {
+                write!(f, "field code %{c} must be an argument on its own")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Splits `exec` into the program and its arguments, expanding the field codes.
+///
+/// Files and URLs are never passed, so `%f`, `%F`, `%u` and `%U` are removed,
+/// as are the deprecated field codes. Field codes inside quoted arguments are
+/// left as they are, because their expansion is undefined, except `%i`, which
+/// expands to two arguments and is rejected.
+pub fn parse(exec: &str, field_codes: &FieldCodes) -> Result<Vec<String>, Error> {
+    let exec = unescape(exec);
+    let mut chars = exec.chars().peekable();
+    let mut args = Vec::new();
+    loop {
+        while chars.next_if_eq(&' ').is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+        parse_arg(&mut chars, field_codes, &mut args)?;
+    }
+    if args.first().map_or(true, |program| program.is_empty()) {
+        return Err(Error::Empty);
+    }
+    Ok(args)
+}
+
+/// Applies the escape rules for values of type string, which come before the quoting rules.
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => unescaped.push(' '),
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some('r') => unescaped.push('\r'),
+            Some('\\') => unescaped.push('\\'),
+            // left for the quoting rules
+            Some(c) => {
+                unescaped.push('\\');
+                unescaped.push(c);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+fn parse_arg(
+    chars: &mut Peekable<Chars>,
+    field_codes: &FieldCodes,
+    args: &mut Vec<String>,
+) -> Result<(), Error> {
+    let mut arg = String::new();
+    let mut quoted = false;
+    while let Some(c) = chars.next_if(|c| *c != ' ') {
+        match c {
+            '"' => {
+                quoted = true;
+                parse_quoted(chars, &mut arg)?;
+            }
+            '\\' => arg.push(chars.next().unwrap_or('\\')),
+            '%' => match chars.next().ok_or(Error::UnfinishedFieldCode)? {
+                '%' => arg.push('%'),
+                'f' | 'F' | 'u' | 'U' | 'd' | 'D' | 'n' | 'N' | 'v' | 'm' => {}
+                'c' => arg.push_str(field_codes.name),
+                'k' => arg.push_str(&field_codes.path.to_string_lossy()),
+                'i' => {
+                    if quoted || !arg.is_empty() || chars.peek().map_or(false, |c| *c != ' ') {
+                        return Err(Error::MisplacedFieldCode('i'));
+                    }
+                    if let Some(icon) = field_codes.icon {
+                        args.push("--icon".into());
+                        args.push(icon.into());
+                    }
+                    return Ok(());
+                }
+                c => return Err(Error::InvalidFieldCode(c)),
+            },
+            c => arg.push(c),
+        }
+    }
+    // arguments which consisted only of removed field codes are dropped entirely
+    if quoted || !arg.is_empty() {
+        args.push(arg);
+    }
+    Ok(())
+}
+
+fn parse_quoted(chars: &mut Peekable<Chars>, arg: &mut String) -> Result<(), Error> {
+    loop {
+        match chars.next().ok_or(Error::UnterminatedQuote)? {
+            '"' => return Ok(()),
+            '%' if chars.peek() == Some(&'i') => return Err(Error::MisplacedFieldCode('i')),
+            '\\' => match chars.next().ok_or(Error::UnterminatedQuote)? {
+                c @ ('"' | '`' | '$' | '\\') => arg.push(c),
+                c => return Err(Error::InvalidEscape(c)),
+            },
+            c => arg.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field_codes() -> FieldCodes<'static> {
+        FieldCodes {
+            icon: Some("firefox"),
+            name: "Firefox Web Browser",
+            path: Path::new("/usr/share/applications/firefox.desktop"),
+        }
+    }
+
+    fn args(exec: &str) -> Vec<String> {
+        parse(exec, &field_codes()).unwrap()
+    }
+
+    #[test]
+    fn splits_arguments() {
+        assert_eq!(args("firefox  --new-window"), ["firefox", "--new-window"]);
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(args(r#"sh -c "a b""#), ["sh", "-c", "a b"]);
+        assert_eq!(args(r#"echo "\\"x\\"""#), ["echo", r#""x""#]);
+        assert_eq!(args(r#"echo "\\`date\\`""#), ["echo", "`date`"]);
+        assert_eq!(args(r#"echo "\\$HOME""#), ["echo", "$HOME"]);
+        // `\\\\` is `\\` after the string escapes and `\` after quoting
+        assert_eq!(args(r#"echo "\\\\""#), ["echo", r"\"]);
+        assert_eq!(args(r#"echo """#), ["echo", ""]);
+    }
+
+    #[test]
+    fn string_escapes() {
+        assert_eq!(args(r"echo\sa"), ["echo", "a"]);
+        assert_eq!(args(r#"echo "a\sb""#), ["echo", "a b"]);
+    }
+
+    #[test]
+    fn expands_field_codes() {
+        assert_eq!(args("printf 100%%"), ["printf", "100%"]);
+        assert_eq!(args("firefox %u"), ["firefox"]);
+        assert_eq!(args("gimp %F --new"), ["gimp", "--new"]);
+        assert_eq!(args("app %f %U"), ["app"]);
+        assert_eq!(args("firefox %i"), ["firefox", "--icon", "firefox"]);
+        assert_eq!(args("app --name=%c"), ["app", "--name=Firefox Web Browser"]);
+        assert_eq!(
+            args("app %k"),
+            ["app", "/usr/share/applications/firefox.desktop"]
+        );
+        // undefined inside quotes, so left alone
+        assert_eq!(args(r#"sh -c "echo %u""#), ["sh", "-c", "echo %u"]);
+    }
+
+    #[test]
+    fn icon_without_icon_key() {
+        let field_codes = FieldCodes {
+            icon: None,
+            ..field_codes()
+        };
+        assert_eq!(
+            parse("app %i --flag", &field_codes).unwrap(),
+            ["app", "--flag"]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = |exec| parse(exec, &field_codes()).unwrap_err();
+        assert_eq!(error(""), Error::Empty);
+        assert_eq!(error("  %f"), Error::Empty);
+        assert_eq!(error(r#""" --flag"#), Error::Empty);
+        assert_eq!(error(r#"sh -c "echo"#), Error::UnterminatedQuote);
+        assert_eq!(error(r#"sh -c "echo\"#), Error::UnterminatedQuote);
+        assert_eq!(error(r#"echo "\a""#), Error::InvalidEscape('a'));
+        assert_eq!(error("app 100%"), Error::UnfinishedFieldCode);
+        assert_eq!(error("app %x"), Error::InvalidFieldCode('x'));
+        assert_eq!(error(r#"app "%i""#), Error::MisplacedFieldCode('i'));
+        assert_eq!(error("app x%i"), Error::MisplacedFieldCode('i'));
+        assert_eq!(error("app %ix"), Error::MisplacedFieldCode('i'));
+    }
+}
@@ -0,0 +1,93 @@
+//! Launch history backing the `Recent` and `Frequent` categories.
+
+use std::cmp;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use serde::{Deserialize, Serialize};
+
+pub const STATE_VERSION: u64 = 1;
+
+/// After this many seconds a launch counts half as much towards the frecency.
+const HALF_LIFE: f64 = 7.0 * 24.0 * 60.0 * 60.0;
+
+#[derive(Clone, CosmicConfigEntry, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct History {
+    pub launches: HashMap<String, LaunchStats>,
+}
+
+/// Launch statistics of an application, keyed by appid in [`History`].
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct LaunchStats {
+    /// Seconds since the unix epoch.
+    pub last_launch: u64,
+    /// Launch count which decays over time, as of `last_launch`.
+    pub frecency: f64,
+}
+
+impl LaunchStats {
+    fn frecency_at(&self, now: u64) -> f64 {
+        let elapsed = now.saturating_sub(self.last_launch) as f64;
+        self.frecency * 0.5_f64.powf(elapsed / HALF_LIFE)
+    }
+}
+
+impl History {
+    /// Records a launch of `appid` and forgets everything which is neither
+    /// among the `keep` most recent nor the `keep` most frequent apps.
+    pub fn record(&mut self, appid: &str, now: u64, keep: usize) {
+        let stats = self.launches.entry(appid.to_string()).or_default();
+        stats.frecency = stats.frecency_at(now) + 1.0;
+        stats.last_launch = now;
+
+        let recent = self.recent(keep);
+        let frequent = self.frequent(now, keep);
+        let retained: Vec<String> = recent
+            .into_iter()
+            .chain(frequent)
+            .map(str::to_string)
+            .collect();
+        self.launches.retain(|appid, _| retained.contains(appid));
+    }
+
+    /// The decayed launch count of `appid`, 0 if it was never launched.
+    pub fn frecency(&self, appid: &str, now: u64) -> f64 {
+        self.launches
+            .get(appid)
+            .map_or(0.0, |stats| stats.frecency_at(now))
+    }
+
+    /// The `count` most recently launched appids, most recent first.
+    pub fn recent(&self, count: usize) -> Vec<&str> {
+        let mut launches: Vec<_> = self.launches.iter().collect();
+        launches.sort_by(|(_, a), (_, b)| b.last_launch.cmp(&a.last_launch));
+        launches
+            .into_iter()
+            .take(count)
+            .map(|(appid, _)| appid.as_str())
+            .collect()
+    }
+
+    /// The `count` appids with the highest frecency, highest first.
+    pub fn frequent(&self, now: u64, count: usize) -> Vec<&str> {
+        let mut launches: Vec<_> = self
+            .launches
+            .iter()
+            .map(|(appid, stats)| (appid, stats.frecency_at(now)))
+            .collect();
+        launches.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(cmp::Ordering::Equal));
+        launches
+            .into_iter()
+            .take(count)
+            .map(|(appid, _)| appid.as_str())
+            .collect()
+    }
+}
+
+/// Seconds since the unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
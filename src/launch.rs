@@ -0,0 +1,143 @@
+//! Launching of desktop entries.
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus};
+use std::time::{Duration, Instant};
+use std::{env, fmt, io, thread};
+
+/// A program exiting unsuccessfully within this time counts as a failed launch.
+const EARLY_EXIT: Duration = Duration::from_secs(2);
+
+/// Terminal emulators probed in order, with the arguments that make them run a program.
+const TERMINALS: &[(&str, &str)] = &[
+    ("cosmic-term", "cosmic-term -e"),
+    ("gnome-terminal", "gnome-terminal --"),
+    ("konsole", "konsole -e"),
+    ("alacritty", "alacritty -e"),
+    ("kitty", "kitty"),
+    ("foot", "foot"),
+    ("xterm", "xterm -e"),
+];
+
+/// What to run when an entry or one of its actions is activated.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Launch {
+    pub appid: String,
+    /// The entry name shown when launching fails, "App – Action" for actions.
+    pub name: String,
+    /// The parsed `Exec` key, see [`crate::exec::parse`].
+    ///
+    /// It is empty if a D-Bus activatable entry has none.
+    pub exec: Vec<String>,
+    pub terminal: bool,
+    /// Whether the program is passed the activation token, from `StartupNotify`.
+    pub startup_notify: bool,
+    /// Whether the app is started with [`crate::dbus_activation`] rather than `exec`.
+    pub dbus_activatable: bool,
+    /// The id of the desktop action to activate, if any.
+    pub action: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum LaunchError {
+    /// The entry has no `Exec` key, so it can only be activated over D-Bus, which failed.
+    NoExec,
+    /// The entry has `Terminal=true`, but no terminal emulator is configured or installed.
+    NoTerminal,
+    /// The program could not be started, e.g. because it does not exist.
+    Spawn(io::Error),
+    /// The program exited unsuccessfully right after starting.
+    Exited(ExitStatus),
+}
+
+impl fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaunchError::NoExec => write!(f, "D-Bus activation failed and there is no Exec key"),
+            LaunchError::NoTerminal => write!(f, "no terminal emulator found"),
+            LaunchError::Spawn(err) => write!(f, "{err}"),
+            LaunchError::Exited(status) => write!(f, "exited immediately with {status}"),
+        }
+    }
+}
+
+impl std::error::Error for LaunchError {}
+
+impl Launch {
+    /// Starts the command, inside `terminal` or an autodetected emulator if the entry asks for one.
+    ///
+    /// The xdg-activation `token` lets the new window take the focus under Wayland.
+    pub fn spawn(&self, terminal: Option<&str>, token: Option<&str>) -> Result<Child, LaunchError> {
+        if self.exec.is_empty() {
+            return Err(LaunchError::NoExec);
+        }
+        let mut argv: Vec<&str> = Vec::with_capacity(self.exec.len() + 2);
+        let terminal = self.terminal.then(|| terminal_command(terminal)).flatten();
+        match &terminal {
+            Some(terminal) => argv.extend(terminal.split_whitespace()),
+            None if self.terminal => return Err(LaunchError::NoTerminal),
+            None => {}
+        }
+        argv.extend(self.exec.iter().map(String::as_str));
+        let Some((program, args)) = argv.split_first() else {
+            return Err(LaunchError::Spawn(io::ErrorKind::InvalidInput.into()));
+        };
+        let mut command = Command::new(program);
+        command.args(args);
+        if let Some(token) = token.filter(|_| self.startup_notify) {
+            command
+                .env("XDG_ACTIVATION_TOKEN", token)
+                .env("DESKTOP_STARTUP_ID", token);
+        }
+        command.spawn().map_err(LaunchError::Spawn)
+    }
+}
+
+/// Waits for up to [`EARLY_EXIT`] to detect programs which fail right away.
+pub fn wait_for_early_exit(mut child: Child) -> Result<(), LaunchError> {
+    let started = Instant::now();
+    while started.elapsed() < EARLY_EXIT {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => return Err(LaunchError::Exited(status)),
+            Ok(None) => thread::sleep(Duration::from_millis(100)),
+            Err(_) => break,
+        }
+    }
+    // reap the child, so it does not linger as a zombie
+    thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+/// The configured terminal command, or the first known terminal emulator in `$PATH`.
+///
+/// The command is split at whitespace and the program is appended to it.
+pub fn terminal_command(terminal: Option<&str>) -> Option<String> {
+    if let Some(terminal) = terminal {
+        return Some(terminal.to_string());
+    }
+    TERMINALS
+        .iter()
+        .find(|(binary, _)| find_in_path(binary).is_some())
+        .map(|(_, command)| command.to_string())
+}
+
+/// Resolves `binary` like a shell would, absolute paths are only checked for being executable.
+pub fn find_in_path(binary: &str) -> Option<PathBuf> {
+    let path = Path::new(binary);
+    if path.is_absolute() {
+        return is_executable(path).then(|| path.to_path_buf());
+    }
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| is_executable(candidate))
+}
+
+fn is_executable(path: &Path) -> bool {
+    path.metadata().map_or(false, |metadata| {
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    })
+}
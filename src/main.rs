@@ -1,13 +1,25 @@
 use crate::window::Window;
 
-use config::{AppListConfig, Config, CONFIG_VERSION};
+use config::{AppListConfig, Config, APP_LIST_CONFIG_VERSION, APP_LIST_ID, CONFIG_VERSION};
 use cosmic::cosmic_config;
 use cosmic::cosmic_config::CosmicConfigEntry;
+use history::{History, STATE_VERSION};
+mod category;
 mod config;
+mod custom_category;
+mod dbus_activation;
+mod dnd;
+mod exec;
+mod history;
 mod mouse_area_copy;
 use window::Flags;
 
+mod launch;
 mod localize;
+mod menu;
+mod notification;
+mod scope;
+mod search;
 mod window;
 
 fn main() -> cosmic::iced::Result {
@@ -29,27 +41,49 @@ fn main() -> cosmic::iced::Result {
             (None, Config::default())
         }
     };
-    let app_list_config = match cosmic_config::Config::new("com.system76.CosmicAppList", 1) {
-        Ok(config_handler) => {
-            let config = match AppListConfig::get_entry(&config_handler) {
+    let (app_list_config_handler, app_list_config) =
+        match cosmic_config::Config::new(APP_LIST_ID, APP_LIST_CONFIG_VERSION) {
+            Ok(config_handler) => {
+                let config = match AppListConfig::get_entry(&config_handler) {
+                    Ok(ok) => ok,
+                    Err((errs, config)) => {
+                        eprintln!("errors loading config: {:?}", errs);
+                        config
+                    }
+                };
+                (Some(config_handler), config)
+            }
+            Err(err) => {
+                eprintln!("failed to create config handler: {}", err);
+                (None, AppListConfig::default())
+            }
+        };
+
+    let (state_handler, history) = match cosmic_config::Config::new_state(window::ID, STATE_VERSION)
+    {
+        Ok(state_handler) => {
+            let history = match History::get_entry(&state_handler) {
                 Ok(ok) => ok,
-                Err((errs, config)) => {
-                    eprintln!("errors loading config: {:?}", errs);
-                    config
+                Err((errs, history)) => {
+                    eprintln!("errors loading state: {:?}", errs);
+                    history
                 }
             };
-            config
+            (Some(state_handler), history)
         }
         Err(err) => {
-            eprintln!("failed to create config handler: {}", err);
-            AppListConfig::default()
+            eprintln!("failed to create state handler: {}", err);
+            (None, History::default())
         }
     };
 
     let flags = Flags {
         config_handler,
         config,
+        app_list_config_handler,
         app_list_config,
+        state_handler,
+        history,
     };
     cosmic::applet::run::<Window>(false, flags)
 }
@@ -0,0 +1,744 @@
+//! Parsing of `.menu` files as described in the Desktop Menu Specification.
+//!
+//! <https://specifications.freedesktop.org/menu-spec/latest/>
+//!
+//! `AppDir`, `LegacyDir` and the like are not supported, the entries are
+//! always those found in the default application directories.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::{env, fmt, fs, io};
+
+use freedesktop_desktop_entry::DesktopEntry;
+use roxmltree::{Document, Node, ParsingOptions};
+
+#[derive(Debug)]
+pub enum Error {
+    /// There is no `applications.menu` in the config directories.
+    NotFound,
+    Io(io::Error),
+    Xml(roxmltree::Error),
+    /// The root element is not `<Menu>`.
+    NotAMenu,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound => write!(f, "no applications.menu found"),
+            Error::Io(err) => write!(f, "{err}"),
+            Error::Xml(err) => write!(f, "{err}"),
+            Error::NotAMenu => write!(f, "root element is not <Menu>"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A `<Menu>` element with everything merged into it.
+#[derive(Clone, Debug, Default)]
+pub struct Menu {
+    pub name: String,
+    directory: Option<String>,
+    /// Later directories take priority.
+    directory_dirs: Vec<PathBuf>,
+    rules: Vec<Rule>,
+    only_unallocated: Option<bool>,
+    deleted: Option<bool>,
+    moves: Vec<(String, String)>,
+    layout: Vec<LayoutItem>,
+    pub submenus: Vec<Menu>,
+}
+
+#[derive(Clone, Debug)]
+enum Rule {
+    Include(Matcher),
+    Exclude(Matcher),
+}
+
+#[derive(Clone, Debug)]
+enum Matcher {
+    Filename(String),
+    Category(String),
+    All,
+    And(Vec<Matcher>),
+    Or(Vec<Matcher>),
+    Not(Vec<Matcher>),
+}
+
+/// Only the order of submenus is taken from `<Layout>`, entries are always sorted by name.
+#[derive(Clone, Debug)]
+enum LayoutItem {
+    Menuname(String),
+    Merge,
+}
+
+/// A desktop entry as seen by the rules of a menu.
+#[derive(Clone, Copy, Debug)]
+pub struct MenuEntry<'a> {
+    /// The desktop file ID.
+    pub id: &'a str,
+    pub categories: &'a [String],
+}
+
+/// A menu after evaluating its rules, empty and deleted menus are gone.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Tree {
+    /// The `<Name>`, which identifies the menu.
+    pub name: String,
+    /// The translated `Name` of the `.directory` file.
+    pub label: Option<String>,
+    /// The `Icon` of the `.directory` file.
+    pub icon: Option<String>,
+    /// Desktop file IDs of the entries directly in this menu.
+    pub entries: Vec<String>,
+    pub submenus: Vec<Tree>,
+}
+
+impl Tree {
+    /// Desktop file IDs of the entries in this menu and all its submenus.
+    pub fn all_entries(&self) -> HashSet<&str> {
+        let mut entries: HashSet<&str> = self.entries.iter().map(String::as_str).collect();
+        for submenu in &self.submenus {
+            entries.extend(submenu.all_entries());
+        }
+        entries
+    }
+
+    /// Finds the submenu at `path`, the names of nested menus are separated by `/`.
+    pub fn find(&self, path: &str) -> Option<&Tree> {
+        path.split('/').try_fold(self, |menu, name| {
+            menu.submenus.iter().find(|submenu| submenu.name == name)
+        })
+    }
+}
+
+impl Matcher {
+    fn matches(&self, entry: &MenuEntry) -> bool {
+        match self {
+            Matcher::Filename(id) => entry.id == id,
+            Matcher::Category(category) => entry.categories.iter().any(|c| c == category),
+            Matcher::All => true,
+            Matcher::And(matchers) => matchers.iter().all(|m| m.matches(entry)),
+            Matcher::Or(matchers) => matchers.iter().any(|m| m.matches(entry)),
+            Matcher::Not(matchers) => !matchers.iter().any(|m| m.matches(entry)),
+        }
+    }
+}
+
+/// Finds and parses `applications.menu`, honoring `$XDG_MENU_PREFIX`.
+pub fn load() -> Result<Menu, Error> {
+    let prefix = env::var("XDG_MENU_PREFIX").unwrap_or_default();
+    let candidates = [
+        format!("{prefix}applications.menu"),
+        "applications.menu".into(),
+    ];
+    let path = candidates
+        .iter()
+        .flat_map(|file| {
+            config_dirs()
+                .into_iter()
+                .map(move |dir| dir.join("menus").join(file))
+        })
+        .find(|path| path.is_file())
+        .ok_or(Error::NotFound)?;
+    parse_file(&path)
+}
+
+pub fn parse_file(path: &Path) -> Result<Menu, Error> {
+    parse_file_inner(path, &mut Vec::new())
+}
+
+/// `visited` holds the files currently being parsed, so merges can not loop.
+fn parse_file_inner(path: &Path, visited: &mut Vec<PathBuf>) -> Result<Menu, Error> {
+    let canonical = path.canonicalize().map_err(Error::Io)?;
+    if visited.contains(&canonical) {
+        return Ok(Menu::default());
+    }
+    let xml = fs::read_to_string(path).map_err(Error::Io)?;
+    visited.push(canonical);
+    let menu = parse_str(&xml, path, visited);
+    visited.pop();
+    menu
+}
+
+fn parse_str(xml: &str, path: &Path, visited: &mut Vec<PathBuf>) -> Result<Menu, Error> {
+    // menu files usually declare the DTD of the specification
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let document = Document::parse_with_options(xml, options).map_err(Error::Xml)?;
+    let root = document.root_element();
+    if root.tag_name().name() != "Menu" {
+        return Err(Error::NotAMenu);
+    }
+    let mut menu = Menu::default();
+    parse_menu(root, &mut menu, path, visited);
+    Ok(menu)
+}
+
+fn parse_menu(node: Node, menu: &mut Menu, path: &Path, visited: &mut Vec<PathBuf>) {
+    let dir = path.parent().unwrap_or(Path::new("/"));
+    for child in node.children().filter(Node::is_element) {
+        let text = child.text().unwrap_or_default().trim();
+        match child.tag_name().name() {
+            "Name" => menu.name = text.to_string(),
+            "Directory" => menu.directory = Some(text.to_string()),
+            "DirectoryDir" => menu.directory_dirs.push(dir.join(text)),
+            "DefaultDirectoryDirs" => menu.directory_dirs.extend(
+                data_dirs()
+                    .into_iter()
+                    .rev()
+                    .map(|dir| dir.join("desktop-directories")),
+            ),
+            "OnlyUnallocated" => menu.only_unallocated = Some(true),
+            "NotOnlyUnallocated" => menu.only_unallocated = Some(false),
+            "Deleted" => menu.deleted = Some(true),
+            "NotDeleted" => menu.deleted = Some(false),
+            "Include" => menu
+                .rules
+                .push(Rule::Include(Matcher::Or(parse_matchers(child)))),
+            "Exclude" => menu
+                .rules
+                .push(Rule::Exclude(Matcher::Or(parse_matchers(child)))),
+            "Menu" => {
+                let mut submenu = Menu::default();
+                parse_menu(child, &mut submenu, path, visited);
+                menu.submenus.push(submenu);
+            }
+            "MergeFile" => {
+                let merge_path = match child.attribute("type") {
+                    Some("parent") => parent_menu_file(path),
+                    _ => Some(dir.join(text)),
+                };
+                if let Some(merge_path) = merge_path {
+                    merge_file(menu, &merge_path, visited);
+                }
+            }
+            "MergeDir" => merge_dir(menu, &dir.join(text), visited),
+            "DefaultMergeDirs" => {
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                let merged = format!("{stem}-merged");
+                for config_dir in config_dirs().into_iter().rev() {
+                    merge_dir(menu, &config_dir.join("menus").join(&merged), visited);
+                }
+            }
+            "Move" => {
+                let find = |name| {
+                    child
+                        .children()
+                        .find(|node| node.has_tag_name(name))
+                        .and_then(|node| node.text())
+                        .map(|text| text.trim().to_string())
+                };
+                if let Some((old, new)) = find("Old").zip(find("New")) {
+                    menu.moves.push((old, new));
+                }
+            }
+            "Layout" => menu.layout = parse_layout(child),
+            _ => {}
+        }
+    }
+}
+
+fn parse_matchers(node: Node) -> Vec<Matcher> {
+    node.children()
+        .filter(Node::is_element)
+        .filter_map(|child| {
+            let text = child.text().unwrap_or_default().trim().to_string();
+            let matcher = match child.tag_name().name() {
+                "Filename" => Matcher::Filename(text),
+                "Category" => Matcher::Category(text),
+                "All" => Matcher::All,
+                "And" => Matcher::And(parse_matchers(child)),
+                "Or" => Matcher::Or(parse_matchers(child)),
+                "Not" => Matcher::Not(parse_matchers(child)),
+                _ => return None,
+            };
+            Some(matcher)
+        })
+        .collect()
+}
+
+fn parse_layout(node: Node) -> Vec<LayoutItem> {
+    node.children()
+        .filter(Node::is_element)
+        .filter_map(|child| match child.tag_name().name() {
+            "Menuname" => Some(LayoutItem::Menuname(
+                child.text().unwrap_or_default().trim().to_string(),
+            )),
+            "Merge" => Some(LayoutItem::Merge),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Merges the contents of the menu file at `path`, missing files are skipped.
+fn merge_file(menu: &mut Menu, path: &Path, visited: &mut Vec<PathBuf>) {
+    match parse_file_inner(path, visited) {
+        Ok(merged) => menu.merge(merged),
+        Err(Error::Io(err)) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => eprintln!("failed to merge menu file {}: {}", path.display(), err),
+    }
+}
+
+fn merge_dir(menu: &mut Menu, dir: &Path, visited: &mut Vec<PathBuf>) {
+    let Ok(read_dir) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = read_dir
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().map_or(false, |ext| ext == "menu"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        merge_file(menu, &path, visited);
+    }
+}
+
+/// The file with the same path relative to a config directory of lower priority than the one of `path`.
+fn parent_menu_file(path: &Path) -> Option<PathBuf> {
+    let config_dirs = config_dirs();
+    let (pos, relative) = config_dirs
+        .iter()
+        .enumerate()
+        .find_map(|(pos, dir)| Some((pos, path.strip_prefix(dir).ok()?)))?;
+    config_dirs[pos + 1..]
+        .iter()
+        .map(|dir| dir.join(relative))
+        .find(|parent| parent.is_file())
+}
+
+/// `$XDG_CONFIG_HOME` followed by `$XDG_CONFIG_DIRS`, highest priority first.
+fn config_dirs() -> Vec<PathBuf> {
+    xdg_dirs("XDG_CONFIG_HOME", ".config", "XDG_CONFIG_DIRS", "/etc/xdg")
+}
+
+/// `$XDG_DATA_HOME` followed by `$XDG_DATA_DIRS`, highest priority first.
+fn data_dirs() -> Vec<PathBuf> {
+    xdg_dirs(
+        "XDG_DATA_HOME",
+        ".local/share",
+        "XDG_DATA_DIRS",
+        "/usr/local/share:/usr/share",
+    )
+}
+
+fn xdg_dirs(
+    home_var: &str,
+    home_default: &str,
+    dirs_var: &str,
+    dirs_default: &str,
+) -> Vec<PathBuf> {
+    let home = env::var_os(home_var)
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| Some(PathBuf::from(env::var_os("HOME")?).join(home_default)));
+    let dirs = env::var(dirs_var)
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| dirs_default.to_string());
+    home.into_iter()
+        .chain(
+            dirs.split(':')
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+        )
+        .collect()
+}
+
+impl Menu {
+    /// Appends the contents of `other`, as if it was written in place of the merge element.
+    fn merge(&mut self, other: Menu) {
+        if other.directory.is_some() {
+            self.directory = other.directory;
+        }
+        self.directory_dirs.extend(other.directory_dirs);
+        self.rules.extend(other.rules);
+        self.only_unallocated = other.only_unallocated.or(self.only_unallocated);
+        self.deleted = other.deleted.or(self.deleted);
+        self.moves.extend(other.moves);
+        if !other.layout.is_empty() {
+            self.layout = other.layout;
+        }
+        self.submenus.extend(other.submenus);
+    }
+
+    /// Merges submenus with the same name, applies `<Move>` and drops deleted submenus.
+    fn consolidate(&mut self) {
+        let mut submenus: Vec<Menu> = Vec::with_capacity(self.submenus.len());
+        for submenu in self.submenus.drain(..) {
+            match submenus.iter_mut().find(|s| s.name == submenu.name) {
+                Some(existing) => existing.merge(submenu),
+                None => submenus.push(submenu),
+            }
+        }
+        self.submenus = submenus;
+
+        for (old, new) in std::mem::take(&mut self.moves) {
+            if let Some(moved) = self.take_path(&old) {
+                self.insert_path(&new, moved);
+            }
+        }
+        self.submenus
+            .retain(|submenu| submenu.deleted != Some(true));
+        self.submenus.iter_mut().for_each(Menu::consolidate);
+    }
+
+    fn take_path(&mut self, path: &str) -> Option<Menu> {
+        match path.split_once('/') {
+            Some((name, rest)) => self
+                .submenus
+                .iter_mut()
+                .find(|submenu| submenu.name == name)?
+                .take_path(rest),
+            None => {
+                let pos = self
+                    .submenus
+                    .iter()
+                    .position(|submenu| submenu.name == path)?;
+                Some(self.submenus.remove(pos))
+            }
+        }
+    }
+
+    fn insert_path(&mut self, path: &str, mut menu: Menu) {
+        let (name, rest) = path.split_once('/').unwrap_or((path, ""));
+        let pos = match self
+            .submenus
+            .iter()
+            .position(|submenu| submenu.name == name)
+        {
+            Some(pos) => pos,
+            None => {
+                self.submenus.push(Menu {
+                    name: name.to_string(),
+                    ..Menu::default()
+                });
+                self.submenus.len() - 1
+            }
+        };
+        if rest.is_empty() {
+            menu.name = name.to_string();
+            self.submenus[pos].merge(menu);
+        } else {
+            self.submenus[pos].insert_path(rest, menu);
+        }
+    }
+
+    /// Evaluates the rules of every menu against `entries`.
+    ///
+    /// Menus with `<OnlyUnallocated>` only get entries which no other menu took.
+    pub fn resolve(mut self, entries: &[MenuEntry], locales: &[String]) -> Tree {
+        self.consolidate();
+        let mut allocated = HashSet::new();
+        let mut tree = self.build(entries, &[], locales, &mut allocated);
+        let unallocated: Vec<MenuEntry> = entries
+            .iter()
+            .filter(|entry| !allocated.contains(entry.id))
+            .copied()
+            .collect();
+        self.fill_unallocated(&mut tree, &unallocated);
+        tree.prune();
+        tree
+    }
+
+    fn build<'a>(
+        &self,
+        entries: &[MenuEntry<'a>],
+        parent_dirs: &[PathBuf],
+        locales: &[String],
+        allocated: &mut HashSet<&'a str>,
+    ) -> Tree {
+        let directory_dirs: Vec<PathBuf> = parent_dirs
+            .iter()
+            .chain(&self.directory_dirs)
+            .cloned()
+            .collect();
+        let (label, icon) = self
+            .directory
+            .as_deref()
+            .and_then(|directory| read_directory(directory, &directory_dirs, locales))
+            .unwrap_or_default();
+        let mut tree = Tree {
+            name: self.name.clone(),
+            label,
+            icon,
+            ..Tree::default()
+        };
+        if self.only_unallocated != Some(true) {
+            let included = self.evaluate(entries);
+            allocated.extend(included.iter().copied());
+            tree.entries = included.into_iter().map(str::to_string).collect();
+        }
+        tree.submenus = self
+            .submenus
+            .iter()
+            .map(|submenu| submenu.build(entries, &directory_dirs, locales, allocated))
+            .collect();
+        tree.submenus = self.order(std::mem::take(&mut tree.submenus));
+        tree
+    }
+
+    fn fill_unallocated(&self, tree: &mut Tree, unallocated: &[MenuEntry]) {
+        if self.only_unallocated == Some(true) {
+            tree.entries = self
+                .evaluate(unallocated)
+                .into_iter()
+                .map(str::to_string)
+                .collect();
+        }
+        for submenu in &self.submenus {
+            if let Some(subtree) = tree.submenus.iter_mut().find(|t| t.name == submenu.name) {
+                submenu.fill_unallocated(subtree, unallocated);
+            }
+        }
+    }
+
+    /// The IDs of the entries matching the rules, which apply in order.
+    fn evaluate<'a>(&self, entries: &[MenuEntry<'a>]) -> Vec<&'a str> {
+        let mut included: Vec<&str> = Vec::new();
+        for rule in &self.rules {
+            match rule {
+                Rule::Include(matcher) => {
+                    for entry in entries {
+                        if matcher.matches(entry) && !included.contains(&entry.id) {
+                            included.push(entry.id);
+                        }
+                    }
+                }
+                Rule::Exclude(matcher) => included.retain(|id| {
+                    entries
+                        .iter()
+                        .find(|entry| entry.id == *id)
+                        .map_or(true, |entry| !matcher.matches(entry))
+                }),
+            }
+        }
+        included
+    }
+
+    /// Orders submenus by `<Layout>`, the ones not named there are sorted by label at `<Merge>`.
+    fn order(&self, mut submenus: Vec<Tree>) -> Vec<Tree> {
+        submenus.sort_by(|a, b| {
+            let a = a.label.as_deref().unwrap_or(&a.name);
+            let b = b.label.as_deref().unwrap_or(&b.name);
+            lexical_sort::natural_lexical_cmp(a, b)
+        });
+        if self.layout.is_empty() {
+            return submenus;
+        }
+        let mut ordered = Vec::with_capacity(submenus.len());
+        let mut merge_at = None;
+        for item in &self.layout {
+            match item {
+                LayoutItem::Menuname(name) => {
+                    if let Some(pos) = submenus.iter().position(|s| s.name == *name) {
+                        ordered.push(submenus.remove(pos));
+                    }
+                }
+                LayoutItem::Merge => merge_at = merge_at.or(Some(ordered.len())),
+            }
+        }
+        let merge_at = merge_at.unwrap_or(ordered.len());
+        ordered.splice(merge_at..merge_at, submenus);
+        ordered
+    }
+}
+
+impl Tree {
+    /// Removes menus without entries, recursively.
+    fn prune(&mut self) {
+        self.submenus.iter_mut().for_each(Tree::prune);
+        self.submenus
+            .retain(|submenu| !submenu.entries.is_empty() || !submenu.submenus.is_empty());
+    }
+}
+
+/// The translated name and icon of a `.directory` file, later directories take priority.
+fn read_directory(
+    directory: &str,
+    directory_dirs: &[PathBuf],
+    locales: &[String],
+) -> Option<(Option<String>, Option<String>)> {
+    let path = directory_dirs
+        .iter()
+        .rev()
+        .map(|dir| dir.join(directory))
+        .find(|path| path.is_file())?;
+    let input = fs::read_to_string(&path).ok()?;
+    let desktop_entry = DesktopEntry::from_str(&path, &input, locales).ok()?;
+    let name = desktop_entry.name(locales).map(|name| name.to_string());
+    let icon = desktop_entry.icon().map(str::to_string);
+    Some((name, icon))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses `tests/fixtures/menus/<file>` and resolves it against `entries`.
+    fn resolve(file: &str, entries: &[(&str, &[&str])], locales: &[&str]) -> Tree {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/menus")
+            .join(file);
+        let entries: Vec<(&str, Vec<String>)> = entries
+            .iter()
+            .map(|(id, categories)| (*id, categories.iter().map(|c| c.to_string()).collect()))
+            .collect();
+        let entries: Vec<MenuEntry> = entries
+            .iter()
+            .map(|(id, categories)| MenuEntry { id, categories })
+            .collect();
+        let locales: Vec<String> = locales.iter().map(|l| l.to_string()).collect();
+        parse_file(&path).unwrap().resolve(&entries, &locales)
+    }
+
+    fn submenus(tree: &Tree) -> Vec<&str> {
+        tree.submenus
+            .iter()
+            .map(|menu| menu.name.as_str())
+            .collect()
+    }
+
+    fn entries<'a>(tree: &'a Tree, path: &str) -> &'a [String] {
+        &tree.find(path).unwrap().entries
+    }
+
+    #[test]
+    fn include_and_exclude() {
+        let tree = resolve(
+            "rules.menu",
+            &[
+                ("gnome-builder.desktop", &["Development"]),
+                ("qtcreator.desktop", &["Development", "Qt"]),
+                ("emacs.desktop", &["Development", "TextEditor"]),
+                ("libreoffice-writer.desktop", &["Office"]),
+                ("evince.desktop", &["Office", "Viewer"]),
+                ("okular.desktop", &["Office", "Viewer", "Qt"]),
+            ],
+            &[],
+        );
+        assert_eq!(entries(&tree, "Development"), ["gnome-builder.desktop"]);
+        assert_eq!(
+            entries(&tree, "Office"),
+            [
+                "emacs.desktop",
+                "libreoffice-writer.desktop",
+                "evince.desktop"
+            ]
+        );
+    }
+
+    #[test]
+    fn only_unallocated() {
+        let tree = resolve(
+            "unallocated.menu",
+            &[
+                ("gimp.desktop", &["Graphics"]),
+                ("libreoffice-writer.desktop", &["Office"]),
+                ("gnome-calculator.desktop", &["Utility"]),
+            ],
+            &[],
+        );
+        assert_eq!(entries(&tree, "Graphics"), ["gimp.desktop"]);
+        assert_eq!(entries(&tree, "Utility"), ["gnome-calculator.desktop"]);
+        assert_eq!(entries(&tree, "Other"), ["libreoffice-writer.desktop"]);
+    }
+
+    #[test]
+    fn deleted() {
+        let tree = resolve(
+            "deleted.menu",
+            &[
+                ("gnome-chess.desktop", &["Game"]),
+                ("libreoffice-writer.desktop", &["Office"]),
+            ],
+            &[],
+        );
+        assert_eq!(submenus(&tree), ["Office"]);
+        assert_eq!(entries(&tree, "Office"), ["libreoffice-writer.desktop"]);
+    }
+
+    #[test]
+    fn moves() {
+        let tree = resolve(
+            "move.menu",
+            &[
+                ("gimp.desktop", &["Graphics"]),
+                ("libreoffice-writer.desktop", &["Office"]),
+                ("gnome-chess.desktop", &["Game"]),
+            ],
+            &[],
+        );
+        assert_eq!(submenus(&tree), ["Office"]);
+        assert_eq!(
+            entries(&tree, "Office"),
+            ["libreoffice-writer.desktop", "gnome-chess.desktop"]
+        );
+        assert_eq!(entries(&tree, "Office/Graphics"), ["gimp.desktop"]);
+    }
+
+    #[test]
+    fn layout_with_merge() {
+        let tree = resolve(
+            "layout.menu",
+            &[
+                ("gnome-builder.desktop", &["Development"]),
+                ("libreoffice-writer.desktop", &["Office"]),
+                ("gimp.desktop", &["Graphics"]),
+                ("gnome-calculator.desktop", &["Utility"]),
+            ],
+            &[],
+        );
+        assert_eq!(
+            submenus(&tree),
+            ["Utility", "Graphics", "Office", "Development"]
+        );
+    }
+
+    #[test]
+    fn merge_file_loops() {
+        let tree = resolve(
+            "loop-a.menu",
+            &[
+                ("gimp.desktop", &["Graphics"]),
+                ("libreoffice-writer.desktop", &["Office"]),
+            ],
+            &[],
+        );
+        assert_eq!(submenus(&tree), ["Graphics", "Office"]);
+        assert_eq!(entries(&tree, "Graphics"), ["gimp.desktop"]);
+    }
+
+    #[test]
+    fn directory_files() {
+        let entries: &[(&str, &[&str])] = &[
+            ("gnome-builder.desktop", &["Development"]),
+            ("gimp.desktop", &["Graphics"]),
+            ("libreoffice-writer.desktop", &["Office"]),
+        ];
+        let tree = resolve("directory.menu", entries, &[]);
+        // sorted by label
+        assert_eq!(submenus(&tree), ["Graphics", "Office", "Development"]);
+        let development = tree.find("Development").unwrap();
+        assert_eq!(development.label.as_deref(), Some("Programming"));
+        assert_eq!(
+            development.icon.as_deref(),
+            Some("applications-development")
+        );
+        let graphics = tree.find("Graphics").unwrap();
+        assert_eq!(graphics.label.as_deref(), Some("Images"));
+        assert_eq!(graphics.icon.as_deref(), Some("image-x-generic"));
+        let office = tree.find("Office").unwrap();
+        assert_eq!(
+            (office.label.as_deref(), office.icon.as_deref()),
+            (None, None)
+        );
+
+        let tree = resolve("directory.menu", entries, &["de"]);
+        let development = tree.find("Development").unwrap();
+        assert_eq!(development.label.as_deref(), Some("Programmierung"));
+    }
+}
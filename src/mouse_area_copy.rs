@@ -12,6 +12,9 @@ use cosmic::iced_core::touch;
 use cosmic::iced_core::widget::{tree, Operation, Tree};
 use cosmic::iced_core::{Clipboard, Element, Layout, Length, Rectangle, Shell, Size, Widget};
 
+/// How far the cursor has to move while pressed to start a drag, so clicks stay clicks.
+const DRAG_THRESHOLD: f32 = 8.0;
+
 /// Emit messages on mouse events.
 #[allow(missing_debug_implementations)]
 pub struct MouseArea<'a, Message, Theme = cosmic::Theme, Renderer = cosmic::iced::Renderer> {
@@ -188,6 +191,22 @@ where
         shell: &mut Shell<'_, Message>,
         viewport: &Rectangle,
     ) -> event::Status {
+        // the content, e.g. a button, captures the press a drag starts with
+        if self.on_drag.is_some() {
+            let state = tree.state.downcast_mut::<State>();
+            match event {
+                Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                | Event::Touch(touch::Event::FingerPressed { .. }) => {
+                    state.drag_initiated = cursor.position_over(layout.bounds());
+                }
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+                | Event::Touch(touch::Event::FingerLifted { .. }) => {
+                    state.drag_initiated = None;
+                }
+                _ => {}
+            }
+        }
+
         if let event::Status::Captured = self.content.as_widget_mut().on_event(
             &mut tree.children[0],
             event.clone(),
@@ -285,7 +304,8 @@ fn update<Message: Clone, Theme, Renderer>(
     shell: &mut Shell<'_, Message>,
     state: &mut State,
 ) -> event::Status {
-    if let Event::Mouse(mouse::Event::CursorMoved { .. })
+    // leaving the surface ends the hover as well
+    if let Event::Mouse(mouse::Event::CursorMoved { .. } | mouse::Event::CursorLeft)
     | Event::Touch(touch::Event::FingerMoved { .. }) = event
     {
         let was_hovered = state.is_hovered;
@@ -311,6 +331,22 @@ fn update<Message: Clone, Theme, Renderer>(
         }
     }
 
+    if let Some((message, drag_source)) = widget.on_drag.as_ref().zip(state.drag_initiated) {
+        if let Some(position) = cursor.position() {
+            if position.distance(drag_source) > DRAG_THRESHOLD {
+                state.drag_initiated = None;
+                shell.publish(message.clone());
+
+                return event::Status::Captured;
+            }
+        }
+    }
+
+    // drags continue outside, everything else only happens within the bounds
+    if !cursor.is_over(layout.bounds()) {
+        return event::Status::Ignored;
+    }
+
     if let Some(message) = widget.on_press.as_ref() {
         if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
         | Event::Touch(touch::Event::FingerPressed { .. }) = event
@@ -365,22 +401,5 @@ fn update<Message: Clone, Theme, Renderer>(
         }
     }
 
-    if state.drag_initiated.is_none() && widget.on_drag.is_some() {
-        if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
-        | Event::Touch(touch::Event::FingerPressed { .. }) = event
-        {
-            state.drag_initiated = cursor.position();
-        }
-    } else if let Some((message, drag_source)) = widget.on_drag.as_ref().zip(state.drag_initiated) {
-        if let Some(position) = cursor.position() {
-            if position.distance(drag_source) > 1.0 {
-                state.drag_initiated = None;
-                shell.publish(message.clone());
-
-                return event::Status::Captured;
-            }
-        }
-    }
-
     event::Status::Ignored
 }
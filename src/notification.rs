@@ -0,0 +1,48 @@
+//! Desktop notifications over the `org.freedesktop.Notifications` D-Bus interface.
+
+// the generated proxy mirrors the D-Bus signature of `Notify`
+#![allow(clippy::too_many_arguments)]
+
+use std::collections::HashMap;
+
+use zbus::zvariant::Value;
+
+use crate::fl;
+
+#[zbus::proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: &HashMap<&str, &Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+}
+
+/// Tells the user that `name` could not be launched.
+pub async fn launch_failed(name: &str, error: &str) -> zbus::Result<()> {
+    let connection = zbus::Connection::session().await?;
+    let proxy = NotificationsProxy::new(&connection).await?;
+    proxy
+        .notify(
+            &fl!("apps-menu"),
+            0,
+            "dialog-error",
+            &fl!("launch-failed", name = name),
+            error,
+            &[],
+            &HashMap::new(),
+            -1,
+        )
+        .await?;
+    Ok(())
+}
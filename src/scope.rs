@@ -0,0 +1,56 @@
+//! Moving launched apps into transient systemd user scopes.
+//!
+//! <https://systemd.io/DESKTOP_ENVIRONMENTS/>
+
+use zbus::zvariant::{OwnedObjectPath, Value};
+use zbus::Connection;
+
+#[zbus::proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1"
+)]
+trait Manager {
+    fn start_transient_unit(
+        &self,
+        name: &str,
+        mode: &str,
+        properties: &[(&str, Value<'_>)],
+        aux: &[(&str, &[(&str, Value<'_>)])],
+    ) -> zbus::Result<OwnedObjectPath>;
+}
+
+/// Moves `pid` into a new `app-cosmic-<appid>-<random>.scope`.
+///
+/// The random part keeps the name unique, unlike the pid, which may be reused
+/// while the scope of its previous process is still loaded.
+pub async fn move_to_scope(appid: &str, pid: u32) -> zbus::Result<()> {
+    let connection = Connection::session().await?;
+    let manager = ManagerProxy::new(&connection).await?;
+    let random = fastrand::u64(..);
+    let name = format!("app-cosmic-{}-{:016x}.scope", escape(appid), random);
+    let properties = [
+        ("PIDs", Value::from(vec![pid])),
+        ("CollectMode", Value::from("inactive-or-failed")),
+    ];
+    manager
+        .start_transient_unit(&name, "fail", &properties, &[])
+        .await?;
+    Ok(())
+}
+
+/// Escapes `appid` for use in a unit name, `-` separates the parts of the name.
+fn escape(appid: &str) -> String {
+    let mut escaped = String::with_capacity(appid.len());
+    for c in appid.chars() {
+        if c.is_ascii_alphanumeric() || matches!(c, ':' | '_' | '.') {
+            escaped.push(c);
+        } else {
+            let mut buf = [0; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                escaped.push_str(&format!("\\x{byte:02x}"));
+            }
+        }
+    }
+    escaped
+}
@@ -0,0 +1,78 @@
+//! Fuzzy matching used by the search field of the popup.
+
+/// Score of a match that starts at the beginning of the haystack.
+const PREFIX: u32 = 800;
+/// Score of a match that starts at the beginning of a word.
+const WORD_START: u32 = 600;
+/// Score of a match anywhere else in the haystack.
+const SUBSTRING: u32 = 400;
+/// Starting score of a subsequence match, before bonuses and penalties.
+const FUZZY: i32 = 300;
+
+/// Scores how well `needle` matches `haystack`, higher is better.
+///
+/// `needle` is expected to be lowercase already. Returns `None` if the
+/// characters of `needle` do not appear in order in `haystack`, or only with
+/// so many characters in between that the match is meaningless.
+pub fn score(needle: &str, haystack: &str) -> Option<u32> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    let haystack = haystack.to_lowercase();
+    if haystack == needle {
+        return Some(PREFIX + 200);
+    }
+    if let Some(pos) = haystack.find(needle) {
+        return Some(if pos == 0 {
+            PREFIX
+        } else if is_word_start(&haystack, pos) {
+            WORD_START
+        } else {
+            SUBSTRING
+        });
+    }
+    fuzzy(needle, &haystack)
+}
+
+fn is_word_start(haystack: &str, pos: usize) -> bool {
+    haystack[..pos]
+        .chars()
+        .next_back()
+        .map_or(true, |c| !c.is_alphanumeric())
+}
+
+/// Matches `needle` as a subsequence of `haystack`, rewarding consecutive
+/// characters and word starts while penalizing the gaps in between.
+fn fuzzy(needle: &str, haystack: &str) -> Option<u32> {
+    let mut needle_chars = needle.chars().peekable();
+    let mut score = FUZZY;
+    let mut started = false;
+    let mut consecutive = false;
+    let mut prev = None;
+    for c in haystack.chars() {
+        let Some(&n) = needle_chars.peek() else {
+            break;
+        };
+        if c == n {
+            needle_chars.next();
+            if consecutive {
+                score += 10;
+            }
+            if prev.map_or(true, |p: char| !p.is_alphanumeric()) {
+                score += 20;
+            }
+            started = true;
+            consecutive = true;
+        } else {
+            if started {
+                score -= 3;
+            }
+            consecutive = false;
+        }
+        prev = Some(c);
+    }
+    if needle_chars.peek().is_some() || score <= 0 {
+        return None;
+    }
+    Some((score as u32).min(SUBSTRING - 1))
+}
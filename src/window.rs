@@ -1,25 +1,50 @@
 #![allow(clippy::needless_return)]
 
-use crate::config::{AppListConfig, Config, CONFIG_VERSION};
+use crate::category;
+use crate::config::{AppListConfig, Config, APP_LIST_CONFIG_VERSION, APP_LIST_ID, CONFIG_VERSION};
+use crate::config::{EntryDetails, EntryLayout, LaunchMode, SortMode};
+use crate::custom_category;
+use crate::dbus_activation;
+use crate::dnd::{self, DesktopFile};
+use crate::exec::{self, FieldCodes};
+use crate::fl;
+use crate::history::{self, History};
+use crate::launch::{self, Launch};
+use crate::menu::{self, MenuEntry};
+use crate::notification;
+use crate::scope;
+use crate::search;
 use cosmic::app::Core;
+use cosmic::applet::cosmic_panel_config::PanelAnchor;
+use cosmic::cctk::sctk::reexports::client::protocol::wl_data_device_manager::DndAction;
 use cosmic::cosmic_config;
 use cosmic::cosmic_theme::Spacing;
 use cosmic::desktop::IconSource;
+use cosmic::iced::alignment::Horizontal;
+use cosmic::iced::event::wayland::{self as wayland_event, DataSourceEvent, OutputEvent};
+use cosmic::iced::event::PlatformSpecific;
+use cosmic::iced::keyboard::{self, key::Named};
+use cosmic::iced::mouse;
+use cosmic::iced::wayland::actions::data_device::DndIcon;
+use cosmic::iced::wayland::data_device::start_drag;
 use cosmic::iced::wayland::popup::{destroy_popup, get_popup};
 use cosmic::iced::window::Id;
 use cosmic::iced::{self, Command, Limits};
-use cosmic::iced_core::Alignment;
-use cosmic::iced_futures::futures::SinkExt;
+use cosmic::iced_core::{Alignment, Border};
+use cosmic::iced_futures::futures::{stream, SinkExt};
 use cosmic::iced_futures::Subscription;
 use cosmic::iced_runtime::core::window;
-use cosmic::iced_style::application;
-use cosmic::iced_widget::scrollable;
+use cosmic::iced_sctk::commands::activation::request_token;
+use cosmic::iced_style::{application, container, text};
+use cosmic::iced_widget::{scrollable, tooltip, Space};
+use cosmic::widget::text_input;
 use cosmic::{widget, Apply};
 use cosmic::{Element, Theme};
 use freedesktop_desktop_entry::DesktopEntry;
 use lexical_sort::natural_lexical_cmp;
 use notify::Watcher;
 use tokio::task::spawn_blocking;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::mouse_area_copy;
 
@@ -29,6 +54,35 @@ use cosmic::iced::Length;
 
 pub const ID: &str = "dev.dominiccgeh.CosmicAppletAppsMenu";
 
+/// Frames of the busy indicator of a launching entry, one per [`SPINNER_INTERVAL`].
+const SPINNER_FRAMES: [&str; 8] = ["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"];
+
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Number of rows skipped by PageUp and PageDown.
+const PAGE_SIZE: usize = 10;
+
+/// Height of an entry of [`EntryLayout::List`], including the padding of its button.
+const LIST_ROW_HEIGHT: f32 = 36.0;
+
+/// Height an entry of [`EntryLayout::List`] gains from [`EntryDetails::Subtitle`].
+const SUBTITLE_HEIGHT: f32 = 16.0;
+
+/// Height of a line of names in [`EntryLayout::Grid`].
+const LABEL_LINE_HEIGHT: f32 = 20.0;
+
+/// Space kept between the popup and the edges of the output and the panel.
+const POPUP_MARGIN: f32 = 8.0;
+
+/// Share of the available height the popup takes without a configured height.
+const POPUP_HEIGHT_FRACTION: f32 = 0.6;
+
+/// Height of the popup without a configured height until the size of the output is known.
+const DEFAULT_POPUP_HEIGHT: f32 = 640.0;
+
+/// Height of the popup besides the entry pane: the search input, padding and spacing.
+const POPUP_CHROME_HEIGHT: f32 = 64.0;
+
 // todo case insensitive categories
 
 pub struct Window {
@@ -36,12 +90,47 @@ pub struct Window {
     popup: Option<Id>,
     config: Config,
     app_list_config: AppListConfig,
-    #[allow(dead_code)]
     config_handler: Option<cosmic_config::Config>,
+    app_list_config_handler: Option<cosmic_config::Config>,
+    state_handler: Option<cosmic_config::Config>,
+    history: History,
     active_category: String,
     timeline: Timeline,
     entry_map: HashMap<String, Vec<Entry>>,
+    /// The resolved `applications.menu`, if `Config::xdg_menu` is set and it could be loaded.
+    menu: Option<menu::Tree>,
     scrollable_id: widget::Id,
+    search_id: widget::Id,
+    search_query: String,
+    focus: Focus,
+    selected_entry: usize,
+    context_menu: Option<String>,
+    launch_error: Option<String>,
+    /// The appid of the entry which is being launched.
+    launching: Option<String>,
+    /// The frame of the busy indicator, see [`SPINNER_FRAMES`].
+    spinner_frame: usize,
+    /// The entry dragged out of the popup, with the surface of its drag icon.
+    dragging: Option<(Id, Entry)>,
+    /// The category being reordered in the sidebar and where it would be dropped.
+    dragged_category: Option<String>,
+    category_drop_target: Option<String>,
+    /// The entry being reordered in a [`SortMode::Manual`] category and where it would be dropped.
+    dragged_entry: Option<String>,
+    entry_drop_target: Option<String>,
+    /// The categories [`prepare_categories`] added, which are not saved with a new order.
+    added_categories: Vec<String>,
+    /// The logical size of the output of the panel, once it is known.
+    output_size: Option<(u32, u32)>,
+}
+
+/// The part of the popup which receives keyboard navigation.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+enum Focus {
+    #[default]
+    Search,
+    Categories,
+    Entries,
 }
 
 #[derive(Clone, Debug)]
@@ -51,17 +140,49 @@ pub enum Message {
     TogglePopup,
     PopupClosed(Id),
     Category(String),
-    SpawnExec(String),
+    SpawnExec(Launch),
+    Activate(Launch, Option<String>),
     Frame(std::time::Instant),
+    /// Advances the busy indicator of the launching entry.
+    Spin,
     NotifyEvent(notify::Event),
-    CategoryUpdate(Option<HashMap<String, Vec<Entry>>>),
+    CategoryUpdate(Option<(HashMap<String, Vec<Entry>>, Option<menu::Tree>)>),
+    Search(String),
+    SearchSubmit,
+    Key(Named),
+    ContextMenu(Option<String>),
+    ToggleFavorite(String),
+    Hide(String),
+    /// Moves an entry of the active category by the offset, for [`SortMode::Manual`].
+    MoveEntry(String, isize),
+    DragEntry(String),
+    HoverEntry(String),
+    DropEntry(String),
+    /// The pointer left the entries, which turns reordering an entry into dragging it out.
+    LeaveEntries,
+    StartDrag(String),
+    DragFinished,
+    DragCategory(String),
+    DropCategory(String),
+    /// A release which ends reordering categories or entries without a drop.
+    ReorderEnd,
+    /// The name and logical size of an output.
+    OutputSize(Option<String>, (i32, i32)),
+    /// The app with this appid was started or activated, it may still fail early.
+    Started(String),
+    /// The appid and name of a launched entry, with the error if it failed.
+    Launched(String, String, Result<(), String>),
+    DismissLaunchError,
 }
 
 #[derive(Clone, Debug)]
 pub struct Flags {
     pub config_handler: Option<cosmic_config::Config>,
     pub config: Config,
+    pub app_list_config_handler: Option<cosmic_config::Config>,
     pub app_list_config: AppListConfig,
+    pub state_handler: Option<cosmic_config::Config>,
+    pub history: History,
 }
 
 impl cosmic::Application for Window {
@@ -83,10 +204,9 @@ impl cosmic::Application for Window {
         flags: Self::Flags,
     ) -> (Self, Command<cosmic::app::Message<Self::Message>>) {
         let mut config = flags.config;
-        if config.sort_categories {
-            config.categories.sort_by(|a, b| category_cmp(a, b));
-        }
+        let added_categories = prepare_categories(&mut config);
         let favorites = flags.app_list_config.favorites.clone();
+        let history = flags.history.clone();
         let entry_map = HashMap::new();
         let window = Window {
             core,
@@ -95,11 +215,30 @@ impl cosmic::Application for Window {
             active_category: config.categories.first().cloned().unwrap_or(String::new()),
             popup: None,
             app_list_config: flags.app_list_config,
+            app_list_config_handler: flags.app_list_config_handler,
+            state_handler: flags.state_handler,
+            history: flags.history,
             entry_map,
+            menu: None,
             timeline: Timeline::new(),
             scrollable_id: widget::Id::unique(),
+            search_id: widget::Id::unique(),
+            search_query: String::new(),
+            focus: Focus::default(),
+            selected_entry: 0,
+            context_menu: None,
+            launch_error: None,
+            launching: None,
+            spinner_frame: 0,
+            dragging: None,
+            dragged_category: None,
+            category_drop_target: None,
+            dragged_entry: None,
+            entry_drop_target: None,
+            added_categories,
+            output_size: None,
         };
-        (window, update_entry_map(favorites, config))
+        (window, update_entry_map(favorites, history, config))
     }
 
     fn on_close_requested(&self, id: window::Id) -> Option<Message> {
@@ -108,7 +247,6 @@ impl cosmic::Application for Window {
 
     fn update(&mut self, message: Self::Message) -> Command<cosmic::app::Message<Self::Message>> {
         // Helper for updating config values efficiently
-        #[allow(unused_macros)]
         macro_rules! config_set {
             ($name: ident, $value: expr) => {
                 match &self.config_handler {
@@ -135,71 +273,295 @@ impl cosmic::Application for Window {
             Message::Config(config) => {
                 if config != self.config {
                     self.config = config.clone();
-                    if self.config.sort_categories {
-                        self.config.categories.sort_by(|a, b| category_cmp(a, b));
-                    }
+                    self.added_categories = prepare_categories(&mut self.config);
                     let favorites = self.app_list_config.favorites.clone();
-                    return update_entry_map(favorites, config);
+                    let history = self.history.clone();
+                    return update_entry_map(favorites, history, config);
                 }
             }
 
             Message::Frame(now) => self.timeline.now(now),
+            Message::Spin => self.spinner_frame = (self.spinner_frame + 1) % SPINNER_FRAMES.len(),
 
             Message::TogglePopup => {
                 return if let Some(p) = self.popup.take() {
+                    self.reset_popup_state();
                     destroy_popup(p)
                 } else {
                     let new_id = Id::unique();
                     self.popup.replace(new_id);
+                    self.focus = Focus::Search;
+                    self.selected_entry = 0;
                     let mut popup_settings =
                         self.core
                             .applet
                             .get_popup_settings(Id::MAIN, new_id, None, None, None);
+                    let (width, height) = self.popup_size();
                     popup_settings.positioner.size_limits = Limits::NONE
-                        .max_width(500.0)
-                        .min_width(300.0)
-                        .min_height(200.0)
-                        .max_height(1080.0);
-                    get_popup(popup_settings)
-                }
+                        .max_width(width)
+                        .min_width(width.min(300.0))
+                        .min_height(height.min(200.0))
+                        .max_height(height);
+                    Command::batch(vec![
+                        get_popup(popup_settings),
+                        text_input::focus(self.search_id.clone()),
+                    ])
+                };
             }
             Message::PopupClosed(id) => {
                 if self.popup.as_ref() == Some(&id) {
                     self.popup = None;
+                    self.reset_popup_state();
                 }
             }
             Message::Category(category) => {
+                // while reordering, hovering a category only marks it as the drop target
+                if self.dragged_category.is_some() {
+                    self.category_drop_target = Some(category);
+                    return Command::none();
+                }
                 if category == self.active_category {
                     return Command::none();
                 }
                 self.active_category = category;
+                self.selected_entry = 0;
+                self.context_menu = None;
                 return scrollable::scroll_to(
                     self.scrollable_id.clone(),
                     scrollable::AbsoluteOffset::default(),
                 );
             }
-            Message::SpawnExec(exec) => {
-                cosmic::desktop::spawn_desktop_exec(exec, Vec::<(&str, &str)>::new());
-                if let Some(p) = self.popup.take() {
-                    return destroy_popup(p);
-                };
+            Message::SpawnExec(launch) => {
+                self.record_launch(&launch.appid);
+                self.update_history_categories();
+                self.launching = Some(launch.appid.clone());
+                // the token is tied to the surface which received the click
+                let surface = self.popup.unwrap_or(Id::MAIN);
+                return request_token(Some(ID.to_string()), Some(surface), move |token| {
+                    cosmic::app::message::app(Message::Activate(launch, token))
+                });
+            }
+            Message::Activate(launch, token) => {
+                return spawn_launch(launch, self.config.clone(), token);
+            }
+            Message::Started(appid) => {
+                // the busy indicator covers the spawn or D-Bus activation, not the window
+                // mapping, which the applet cannot observe; the new window may have closed
+                // the popup already
+                if self.launching.as_ref() == Some(&appid) {
+                    self.launching = None;
+                    if let Some(p) = self.popup.take() {
+                        self.reset_popup_state();
+                        return destroy_popup(p);
+                    }
+                }
             }
+            Message::Launched(appid, name, result) => {
+                if self.launching.as_ref() == Some(&appid) {
+                    self.launching = None;
+                }
+                if let Err(err) = result {
+                    eprintln!("failed to launch {}: {}", appid, err);
+                    self.launch_error = Some(fl!("launch-failed", name = name) + ": " + &err);
+                }
+            }
+            Message::DismissLaunchError => self.launch_error = None,
             Message::AppListConfg(config) => {
                 if config != self.app_list_config {
                     let favorites = config.favorites.clone();
                     self.app_list_config = config;
+                    let history = self.history.clone();
                     let config = self.config.clone();
-                    return update_entry_map(favorites, config);
+                    return update_entry_map(favorites, history, config);
                 }
             }
             Message::NotifyEvent(_event) => {
                 let favorites = self.app_list_config.favorites.clone();
+                let history = self.history.clone();
                 let config = self.config.clone();
-                return update_entry_map(favorites, config);
+                return update_entry_map(favorites, history, config);
             }
-            Message::CategoryUpdate(entry_map) => {
-                if let Some(entry_map) = entry_map {
+            Message::CategoryUpdate(update) => {
+                if let Some((entry_map, menu)) = update {
                     self.entry_map = entry_map;
+                    self.menu = menu;
+                }
+            }
+            Message::Search(query) => {
+                self.search_query = query;
+                self.focus = Focus::Search;
+                self.selected_entry = 0;
+                return scrollable::scroll_to(
+                    self.scrollable_id.clone(),
+                    scrollable::AbsoluteOffset::default(),
+                );
+            }
+            Message::SearchSubmit => {
+                let selected = match self.focus {
+                    Focus::Entries => self.selected_entry,
+                    _ => 0,
+                };
+                let launch = self
+                    .active_entries()
+                    .get(selected)
+                    .map(|entry| entry.launch());
+                if let Some(launch) = launch {
+                    return self.update(Message::SpawnExec(launch));
+                }
+            }
+            Message::Key(key) => {
+                if self.popup.is_some() {
+                    return self.handle_key(key);
+                }
+            }
+            Message::ContextMenu(appid) => {
+                self.context_menu = if appid == self.context_menu {
+                    None
+                } else {
+                    appid
+                };
+            }
+            Message::ToggleFavorite(appid) => {
+                self.context_menu = None;
+                let mut favorites = self.app_list_config.favorites.clone();
+                if let Some(pos) = favorites.iter().position(|favorite| *favorite == appid) {
+                    favorites.remove(pos);
+                } else {
+                    favorites.push(appid);
+                }
+                // written back to the dock's config, so both stay in sync
+                match &self.app_list_config_handler {
+                    Some(config_handler) => {
+                        if let Err(err) = self
+                            .app_list_config
+                            .set_favorites(config_handler, favorites)
+                        {
+                            eprintln!("failed to save config \"favorites\": {}", err);
+                        }
+                    }
+                    None => {
+                        self.app_list_config.favorites = favorites;
+                        eprintln!("failed to save config \"favorites\": no config handler");
+                    }
+                }
+                let favorites = self.app_list_config.favorites.clone();
+                let history = self.history.clone();
+                let config = self.config.clone();
+                return update_entry_map(favorites, history, config);
+            }
+            Message::Hide(appid) => {
+                self.context_menu = None;
+                let mut overrides = self.config.overrides.clone();
+                overrides.entry(appid).or_default().hide = true;
+                config_set!(overrides, overrides);
+                let favorites = self.app_list_config.favorites.clone();
+                let history = self.history.clone();
+                let config = self.config.clone();
+                return update_entry_map(favorites, history, config);
+            }
+            Message::MoveEntry(appid, offset) => {
+                let mut order: Vec<String> = self
+                    .active_entries()
+                    .into_iter()
+                    .map(|entry| entry.appid.clone())
+                    .collect();
+                let Some(pos) = order.iter().position(|a| *a == appid) else {
+                    return Command::none();
+                };
+                let new_pos = pos.saturating_add_signed(offset).min(order.len() - 1);
+                let appid = order.remove(pos);
+                order.insert(new_pos, appid);
+                let mut manual_order = self.config.manual_order.clone();
+                manual_order.insert(self.active_category.clone(), order);
+                config_set!(manual_order, manual_order);
+                let favorites = self.app_list_config.favorites.clone();
+                let history = self.history.clone();
+                let config = self.config.clone();
+                return update_entry_map(favorites, history, config);
+            }
+            Message::StartDrag(appid) => {
+                let Some(popup) = self.popup else {
+                    return Command::none();
+                };
+                let entry = self
+                    .active_entries()
+                    .into_iter()
+                    .find(|entry| entry.appid == appid)
+                    .cloned();
+                let Some(entry) = entry else {
+                    return Command::none();
+                };
+                self.context_menu = None;
+                let icon_id = Id::unique();
+                let desktop_file = DesktopFile(entry.path.clone());
+                self.dragging = Some((icon_id, entry));
+                return start_drag(
+                    vec![dnd::URI_LIST.to_string()],
+                    DndAction::Copy,
+                    popup,
+                    Some(DndIcon::Custom(icon_id)),
+                    Box::new(desktop_file),
+                );
+            }
+            Message::DragFinished => self.dragging = None,
+            Message::DragCategory(category) => {
+                self.category_drop_target = Some(category.clone());
+                self.dragged_category = Some(category);
+            }
+            Message::DropCategory(target) => {
+                let dragged = self.dragged_category.take();
+                self.end_reorder();
+                let Some(dragged) = dragged else {
+                    return Command::none();
+                };
+                let mut categories = self.config.categories.clone();
+                let from = categories.iter().position(|c| *c == dragged);
+                let to = categories.iter().position(|c| *c == target);
+                if let Some((from, to)) = from.zip(to).filter(|(from, to)| from != to) {
+                    let category = categories.remove(from);
+                    categories.insert(to, category);
+                    categories.retain(|category| !self.added_categories.contains(category));
+                    // a sorted list would undo the new order right away
+                    config_set!(sort_categories, false);
+                    config_set!(categories, categories);
+                    self.added_categories = prepare_categories(&mut self.config);
+                }
+            }
+            Message::DragEntry(appid) => {
+                self.context_menu = None;
+                self.entry_drop_target = Some(appid.clone());
+                self.dragged_entry = Some(appid);
+            }
+            Message::HoverEntry(appid) => {
+                if self.dragged_entry.is_some() {
+                    self.entry_drop_target = Some(appid);
+                }
+            }
+            Message::DropEntry(target) => {
+                let dragged = self.dragged_entry.take();
+                self.end_reorder();
+                let Some(dragged) = dragged else {
+                    return Command::none();
+                };
+                let entries = self.active_entries();
+                let from = entries.iter().position(|entry| entry.appid == dragged);
+                let to = entries.iter().position(|entry| entry.appid == target);
+                if let Some((from, to)) = from.zip(to).filter(|(from, to)| from != to) {
+                    return self.update(Message::MoveEntry(dragged, to as isize - from as isize));
+                }
+            }
+            // a dragged entry which leaves the entries is dragged out instead
+            Message::LeaveEntries => {
+                if let Some(appid) = self.dragged_entry.take() {
+                    self.end_reorder();
+                    return self.update(Message::StartDrag(appid));
+                }
+            }
+            // a release which no category or entry received cancels the reordering
+            Message::ReorderEnd => self.end_reorder(),
+            Message::OutputSize(name, (width, height)) => {
+                if name.as_deref() == Some(self.core.applet.output_name.as_str()) {
+                    self.output_size = Some((width.max(0) as u32, height.max(0) as u32));
                 }
             }
         }
@@ -214,7 +576,12 @@ impl cosmic::Application for Window {
             .into()
     }
 
-    fn view_window(&self, _id: Id) -> Element<Self::Message> {
+    fn view_window(&self, id: Id) -> Element<Self::Message> {
+        if let Some((icon_id, entry)) = &self.dragging {
+            if *icon_id == id {
+                return entry.icon.as_cosmic_icon().size(32).into();
+            }
+        }
         #[allow(unused_variables)]
         let Spacing {
             space_xxxs,
@@ -225,16 +592,48 @@ impl cosmic::Application for Window {
             ..
         } = self.core.system_theme().cosmic().spacing;
 
-        let mut content_list = widget::column::with_capacity(1).padding([8, 0]);
+        let mut content_list = widget::column::with_capacity(2)
+            .padding([8, 0])
+            .spacing(space_xs);
         let mut rows = widget::row::with_capacity(2);
-        let Config { categories, .. } = &self.config;
         let mut left_side = widget::grid().row_spacing(0);
 
-        let empty_vec = Vec::new();
-        let active_entries = self
-            .entry_map
-            .get(&self.active_category)
-            .unwrap_or(&empty_vec);
+        let search = text_input::search_input(fl!("search"), &self.search_query)
+            .id(self.search_id.clone())
+            .on_input(Message::Search)
+            .on_clear(Message::Search(String::new()))
+            .on_submit(Message::SearchSubmit)
+            .apply(widget::container)
+            .padding([0, space_xs]);
+        content_list = content_list.push(search);
+
+        if let Some(launch_error) = &self.launch_error {
+            let close = widget::button(
+                widget::icon::from_name("window-close-symbolic")
+                    .size(16)
+                    .icon(),
+            )
+            .on_press(Message::DismissLaunchError)
+            .style(cosmic::theme::Button::Icon);
+            let banner = widget::row::with_capacity(3)
+                .push(
+                    widget::icon::from_name("dialog-error-symbolic")
+                        .size(16)
+                        .icon(),
+                )
+                .push(widget::text(launch_error).width(Length::Fill))
+                .push(close)
+                .spacing(space_xxs)
+                .align_items(Alignment::Center)
+                .apply(widget::container)
+                .padding([space_xxs, space_xs])
+                .style(cosmic::theme::Container::Card);
+            content_list = content_list.push(widget::container(banner).padding([0, space_xs]));
+        }
+
+        let searching = self.searching();
+        let active_entries = self.active_entries();
+        let categories = self.visible_categories();
 
         // HACK: determine the largest item and do not set the width to Fill
         // alternative might be a mouse area which return the bounds of the widget
@@ -243,63 +642,176 @@ impl cosmic::Application for Window {
         let mut max_width = 0;
         let mut max_category = None;
 
-        for category in categories {
-            if self.config.skip_empty_categories && !self.entry_map.contains_key(category) {
-                continue;
-            }
-            let count = unicode_display_width::width(&category);
+        let labels: Vec<String> = categories
+            .iter()
+            .map(|category| self.category_label(category))
+            .collect();
+        let icons: Vec<Option<String>> = categories
+            .iter()
+            .map(|category| self.category_icon(category))
+            .collect();
+        for ((&category, label), icon) in categories.iter().zip(&labels).zip(&icons) {
+            // an icon takes about as much space as a few characters, as does the indentation
+            let icon_width = if icon.is_some() { 3 } else { 0 };
+            let indent_width = if category.contains('/') { 2 } else { 0 };
+            let count = unicode_display_width::width(label) + icon_width + indent_width;
             if count > max_width {
                 max_width = count;
                 max_category = Some(category);
             }
         }
-        for category in categories {
-            if self.config.skip_empty_categories && !self.entry_map.contains_key(category) {
-                continue;
+        for ((category, label), icon) in categories.into_iter().zip(labels).zip(icons) {
+            let mut content = widget::row::with_capacity(3)
+                .spacing(space_xxs)
+                .align_items(Alignment::Center);
+            if category.contains('/') {
+                content = content.push(Space::with_width(space_s));
+            }
+            if let Some(icon) = icon {
+                content = content.push(widget::icon::from_name(icon).size(16).icon());
             }
-            let txt = widget::text(category)
+            let txt = content
+                .push(widget::text(label))
                 .apply(widget::container)
                 .padding([0, space_xxxs]);
 
             let mut btn = widget::button(txt)
-                .on_press(Message::Category(category.clone()))
                 .selected(self.active_category == *category)
                 .style(cosmic::theme::Button::HeaderBar);
+            // the dragged category's button would take the release that drops it onto itself
+            if self.dragged_category.as_ref() != Some(category) {
+                btn = btn.on_press(Message::Category(category.clone()));
+            }
 
             if max_category.map_or(true, |max| max != category) {
                 btn = btn.width(Length::Fill);
             }
-            let area =
-                mouse_area_copy::MouseArea::new(btn).on_enter(Message::Category(category.clone()));
+            let mut container = widget::container(btn);
+            let highlighted = match &self.dragged_category {
+                Some(_) => self.category_drop_target.as_ref() == Some(category),
+                None => self.focus == Focus::Categories && self.active_category == *category,
+            };
+            if highlighted {
+                container = container.style(cosmic::theme::Container::custom(focus_ring));
+            }
+            let mut area = mouse_area_copy::MouseArea::new(container)
+                .on_enter(Message::Category(category.clone()));
+            // only configured categories have a position which can be saved
+            if self.config.categories.contains(category) {
+                area = area.on_release(Message::DropCategory(category.clone()));
+                if !self.added_categories.contains(category) {
+                    area = area.on_drag(Message::DragCategory(category.clone()));
+                }
+            }
             left_side = left_side.push(area).insert_row();
         }
-        let mut right_side = widget::column::with_capacity(active_entries.len());
-
-        for entry in active_entries {
-            let txt = widget::text(entry.name.clone()).width(Length::Fill);
+        let layout = self.entry_layout();
+        let (cell_width, cell_height) = grid_cell_size(&self.config);
+        let mut entry_elements: Vec<Element<Message>> = Vec::with_capacity(active_entries.len());
+        let manual_order = self.manual_order();
 
-            let icon = entry.icon.as_cosmic_icon().size(20);
-            let row = widget::row::with_capacity(2)
-                .push(icon)
-                .push(txt)
-                .spacing(space_xxs)
-                .align_items(Alignment::Center);
-            let btn = widget::button(row)
-                .on_press(Message::SpawnExec(entry.exec.clone()))
-                .style(cosmic::theme::Button::HeaderBar);
-            let container = widget::container(btn).width(Length::Fill);
-            right_side = right_side.push(container);
+        for (i, entry) in active_entries.iter().enumerate() {
+            let content = match layout {
+                EntryLayout::List => self.list_entry_view(entry),
+                EntryLayout::Grid => self.grid_entry_view(entry),
+            };
+            let mut btn = widget::button(content).style(cosmic::theme::Button::HeaderBar);
+            // the dragged entry's button would take the release that drops it onto itself
+            if self.dragged_entry.as_ref() != Some(&entry.appid) {
+                btn = btn.on_press(Message::SpawnExec(entry.launch()));
+            }
+            let width = match layout {
+                EntryLayout::List => Length::Fill,
+                EntryLayout::Grid => Length::Fixed(cell_width),
+            };
+            let mut container = widget::container(btn).width(width);
+            let highlighted = match &self.dragged_entry {
+                Some(_) => self.entry_drop_target.as_ref() == Some(&entry.appid),
+                None => self.focus == Focus::Entries && self.selected_entry == i,
+            };
+            if highlighted {
+                container = container.style(cosmic::theme::Container::custom(focus_ring));
+            }
+            let mut area = mouse_area_copy::MouseArea::new(container)
+                .on_right_press(Message::ContextMenu(Some(entry.appid.clone())));
+            // in a manual order, dragging moves entries until they leave the entries
+            area = if manual_order {
+                area.on_drag(Message::DragEntry(entry.appid.clone()))
+                    .on_enter(Message::HoverEntry(entry.appid.clone()))
+                    .on_release(Message::DropEntry(entry.appid.clone()))
+            } else {
+                area.on_drag(Message::StartDrag(entry.appid.clone()))
+            };
+            if self.context_menu.as_ref() == Some(&entry.appid) {
+                let popover = widget::popover(area)
+                    .popup(self.context_menu_view(entry))
+                    .position(widget::popover::Position::Bottom);
+                entry_elements.push(popover.into());
+            } else if let Some(details) = self.tooltip_details(entry, layout) {
+                let details = widget::text(details)
+                    .apply(widget::container)
+                    .padding(space_xxs)
+                    .style(cosmic::theme::Container::Dropdown);
+                let tooltip = tooltip(area, details, tooltip::Position::Bottom);
+                entry_elements.push(tooltip.into());
+            } else {
+                entry_elements.push(area.into());
+            }
+        }
+        let (mut right_side, row_height) = match layout {
+            EntryLayout::List => {
+                let row_height = if self.config.entry_details == EntryDetails::Subtitle {
+                    LIST_ROW_HEIGHT + SUBTITLE_HEIGHT
+                } else {
+                    LIST_ROW_HEIGHT
+                };
+                (widget::column::with_children(entry_elements), row_height)
+            }
+            EntryLayout::Grid => {
+                let columns = self.entry_columns();
+                let mut grid =
+                    widget::column::with_capacity(active_entries.len().div_ceil(columns))
+                        .spacing(space_xxs);
+                let mut entry_elements = entry_elements.into_iter().peekable();
+                while entry_elements.peek().is_some() {
+                    let row =
+                        widget::row::with_children(entry_elements.by_ref().take(columns).collect())
+                            .spacing(space_xxs);
+                    grid = grid.push(row);
+                }
+                (grid, cell_height + space_xxs as f32)
+            }
+        };
+        if searching && active_entries.is_empty() {
+            right_side = right_side.push(
+                widget::text(fl!("no-results"))
+                    .apply(widget::container)
+                    .padding([0, space_s]),
+            );
         }
+        // only whole rows, so none is cut off at the bottom
+        let (_, popup_height) = self.popup_size();
+        let mut max_height = popup_height - POPUP_CHROME_HEIGHT;
+        if let Some(max_visible_rows) = self.config.max_visible_rows {
+            max_height = max_height.min(max_visible_rows as f32 * row_height);
+        }
+        let height = (max_height / row_height).floor().max(1.0) * row_height;
         let right_scroll = widget::scrollable(right_side)
-            .height(500)
+            .height(height)
             .id(self.scrollable_id.clone());
 
-        let left_container = widget::container(left_side).width(Length::Shrink);
+        // while searching the results replace the category view
+        if !searching {
+            // on small outputs the categories may not fit next to the entries either
+            let left_container = widget::container(widget::scrollable(left_side))
+                .width(Length::Shrink)
+                .max_height(height);
+            rows = rows.push(left_container);
+        }
         let right_container = widget::container(right_scroll).width(Length::Fill);
-        rows = rows
-            .push(left_container)
-            .push(right_container)
-            .spacing(space_xs);
+        let right_area =
+            mouse_area_copy::MouseArea::new(right_container).on_exit(Message::LeaveEntries);
+        rows = rows.push(right_area).spacing(space_xs);
         content_list = content_list.push(rows);
 
         self.core.applet.popup_container(content_list).into()
@@ -323,8 +835,8 @@ impl cosmic::Application for Window {
         });
         let app_list_config = cosmic_config::config_subscription(
             std::any::TypeId::of::<AppListConfigSubscription>(),
-            "com.system76.CosmicAppList".into(),
-            1,
+            APP_LIST_ID.into(),
+            APP_LIST_CONFIG_VERSION,
         )
         .map(|update| {
             if !update.errors.is_empty() {
@@ -376,7 +888,63 @@ impl cosmic::Application for Window {
             .as_subscription()
             .map(|(_, now)| Message::Frame(now));
 
-        Subscription::batch(vec![config, app_list_config, watcher, timeline])
+        let spinner = if self.launching.is_some() {
+            iced::time::every(SPINNER_INTERVAL).map(|_| Message::Spin)
+        } else {
+            Subscription::none()
+        };
+
+        let keyboard = iced::event::listen_with(|event, status| match event {
+            iced::Event::Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(key),
+                ..
+            }) => {
+                // the search input submits on its own
+                if key == Named::Enter && status == iced::event::Status::Captured {
+                    return None;
+                }
+                Some(Message::Key(key))
+            }
+            _ => None,
+        });
+
+        let drag = iced::event::listen_with(|event, status| match event {
+            iced::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                wayland_event::Event::DataSource(
+                    DataSourceEvent::DndFinished | DataSourceEvent::Cancelled,
+                ),
+            )) => Some(Message::DragFinished),
+            // a category or entry which received the release captured it and ends the drag itself
+            iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+                if status == iced::event::Status::Ignored =>
+            {
+                Some(Message::ReorderEnd)
+            }
+            _ => None,
+        });
+
+        let output = iced::event::listen_with(|event, _| match event {
+            iced::Event::PlatformSpecific(PlatformSpecific::Wayland(
+                wayland_event::Event::Output(
+                    OutputEvent::Created(Some(info)) | OutputEvent::InfoUpdate(info),
+                    _,
+                ),
+            )) => info
+                .logical_size
+                .map(|size| Message::OutputSize(info.name, size)),
+            _ => None,
+        });
+
+        Subscription::batch(vec![
+            config,
+            app_list_config,
+            watcher,
+            timeline,
+            spinner,
+            keyboard,
+            drag,
+            output,
+        ])
     }
 
     fn style(&self) -> Option<<Theme as application::StyleSheet>::Style> {
@@ -384,55 +952,706 @@ impl cosmic::Application for Window {
     }
 }
 
+/// The steps of [`spawn_launch`], which reports the start and the outcome of a launch.
+enum LaunchStep {
+    Start(Launch, Config, Option<String>),
+    /// The program is running, watch for an early exit.
+    Wait(Launch, Child),
+    Done,
+}
+
+/// Runs `launch` off the main thread and reports failures with a desktop notification.
+///
+/// [`Message::Started`] lets the popup close right away, the early exit detection
+/// continues in the background and ends with [`Message::Launched`].
+fn spawn_launch(
+    launch: Launch,
+    config: Config,
+    token: Option<String>,
+) -> Command<cosmic::app::Message<Message>> {
+    let steps = stream::unfold(
+        LaunchStep::Start(launch, config, token),
+        |step| async move {
+            match step {
+                LaunchStep::Start(launch, config, token) => {
+                    match start_launch(&launch, &config, token.as_deref()).await {
+                        Ok(Some(child)) => Some((
+                            Message::Started(launch.appid.clone()),
+                            LaunchStep::Wait(launch, child),
+                        )),
+                        Ok(None) => Some((Message::Started(launch.appid), LaunchStep::Done)),
+                        Err(err) => Some((launch_failed(launch, err).await, LaunchStep::Done)),
+                    }
+                }
+                LaunchStep::Wait(launch, child) => {
+                    let result =
+                        match spawn_blocking(move || launch::wait_for_early_exit(child)).await {
+                            Ok(result) => result.map_err(|err| err.to_string()),
+                            Err(err) => Err(err.to_string()),
+                        };
+                    let message = match result {
+                        Ok(()) => Message::Launched(launch.appid, launch.name, Ok(())),
+                        Err(err) => launch_failed(launch, err).await,
+                    };
+                    Some((message, LaunchStep::Done))
+                }
+                LaunchStep::Done => None,
+            }
+        },
+    );
+    return Command::run(steps, cosmic::app::message::app);
+}
+
+/// Activates or spawns `launch`, the child is returned unless it was activated over D-Bus.
+async fn start_launch(
+    launch: &Launch,
+    config: &Config,
+    token: Option<&str>,
+) -> Result<Option<Child>, String> {
+    if launch.dbus_activatable {
+        let action = launch.action.as_deref();
+        match dbus_activation::activate(&launch.appid, action, token).await {
+            Ok(()) => return Ok(None),
+            Err(err) => eprintln!(
+                "failed to activate {} over D-Bus, falling back to Exec: {}",
+                launch.appid, err
+            ),
+        }
+    }
+    let child = launch
+        .spawn(config.terminal.as_deref(), token)
+        .map_err(|err| err.to_string())?;
+    if config.launch_mode == LaunchMode::Scope {
+        if let Err(err) = scope::move_to_scope(&launch.appid, child.id()).await {
+            eprintln!("failed to move {} to a scope: {}", launch.appid, err);
+        }
+    }
+    Ok(Some(child))
+}
+
+/// Sends the desktop notification for a failed launch, the banner is left to the message.
+async fn launch_failed(launch: Launch, err: String) -> Message {
+    if let Err(notify_err) = notification::launch_failed(&launch.name, &err).await {
+        eprintln!("failed to send notification: {}", notify_err);
+    }
+    Message::Launched(launch.appid, launch.name, Err(err))
+}
+
 fn update_entry_map(
     favorites: Vec<String>,
+    history: History,
     config: Config,
 ) -> Command<cosmic::app::Message<Message>> {
     return Command::perform(
         async move {
-            spawn_blocking(move || entry_map(entries(&config), favorites, &config))
-                .await
-                .ok()
+            spawn_blocking(move || {
+                let entries = entries(&config);
+                let menu = config.xdg_menu.then(|| xdg_menu(&entries)).flatten();
+                let entry_map = entry_map(entries, favorites, &history, menu.as_ref(), &config);
+                (entry_map, menu)
+            })
+            .await
+            .ok()
         },
-        |entry_map| cosmic::app::message::app(Message::CategoryUpdate(entry_map)),
+        |update| cosmic::app::message::app(Message::CategoryUpdate(update)),
     );
 }
 use std::borrow::Cow;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::time::{Duration, UNIX_EPOCH};
 use std::{cmp, fs};
-impl Window {}
+impl Window {
+    fn searching(&self) -> bool {
+        !self.search_query.trim().is_empty()
+    }
+
+    /// Categories shown in the sidebar, in display order.
+    ///
+    /// With an XDG menu, its submenus replace the configured categories, apart from
+    /// `Favorites`, `Recent` and `Frequent`.
+    fn visible_categories(&self) -> Vec<&String> {
+        let categories: Vec<&String> = match &self.menu {
+            Some(menu) => self
+                .config
+                .categories
+                .iter()
+                .filter(|category| {
+                    matches!(category.as_str(), "Favorites" | "Recent" | "Frequent")
+                        || self.config.custom_categories.contains_key(*category)
+                })
+                .chain(menu.submenus.iter().map(|submenu| &submenu.name))
+                .chain(
+                    self.entry_map
+                        .get_key_value("Other")
+                        .map(|(other, _)| other),
+                )
+                .collect(),
+            None => self.config.categories.iter().collect(),
+        };
+        // the sub-categories of the active main category are expanded below it
+        let expanded = self.active_category.split('/').next().unwrap_or_default();
+        let mut visible = Vec::with_capacity(categories.len());
+        for category in categories {
+            if self.config.skip_empty_categories && !self.entry_map.contains_key(category) {
+                continue;
+            }
+            visible.push(category);
+            if category == expanded {
+                visible.extend(self.sub_categories(category));
+            }
+        }
+        visible
+    }
+
+    /// The sub-categories of `main` which have entries, in display order.
+    fn sub_categories(&self, main: &str) -> Vec<&String> {
+        let prefix = format!("{main}/");
+        let mut sub_categories: Vec<&String> = self
+            .entry_map
+            .keys()
+            .filter(|category| {
+                category
+                    .strip_prefix(&prefix)
+                    .map_or(false, |sub| !sub.contains('/'))
+            })
+            .collect();
+        match self.menu.as_ref().and_then(|menu| menu.find(main)) {
+            // submenus are already in the order of the menu's layout
+            Some(menu) => sub_categories.sort_by_key(|category| {
+                menu.submenus.iter().position(|submenu| {
+                    Some(submenu.name.as_str()) == category::sub_category(category)
+                })
+            }),
+            None => sub_categories.sort_by(|a, b| {
+                natural_lexical_cmp(&self.category_label(a), &self.category_label(b))
+            }),
+        }
+        sub_categories
+    }
+
+    /// Entries shown in the right pane, either search results or the active category.
+    fn active_entries(&self) -> Vec<&Entry> {
+        if self.searching() {
+            return self.search_results();
+        }
+        self.entry_map
+            .get(&self.active_category)
+            .map(|entries| entries.iter().collect())
+            .unwrap_or_default()
+    }
+
+    fn handle_key(&mut self, key: Named) -> Command<cosmic::app::Message<Message>> {
+        let searching = self.searching();
+        match (self.focus, key) {
+            (_, Named::Escape) if self.context_menu.is_some() => self.context_menu = None,
+            (_, Named::Escape) => return self.update(Message::TogglePopup),
+            (Focus::Search, Named::ArrowDown | Named::Tab) => {
+                if searching {
+                    self.focus = Focus::Entries;
+                    self.selected_entry = 0;
+                } else {
+                    self.focus = Focus::Categories;
+                }
+                // HACK: focusing an id no widget has unfocuses the search input
+                return text_input::focus(widget::Id::unique());
+            }
+            (Focus::Search, _) => {}
+            (Focus::Categories, Named::ArrowUp | Named::ArrowDown) => {
+                let categories: Vec<String> =
+                    self.visible_categories().into_iter().cloned().collect();
+                let pos = categories.iter().position(|c| *c == self.active_category);
+                let next = match (key, pos) {
+                    (Named::ArrowUp, Some(0)) => return self.focus_search(),
+                    (Named::ArrowUp, Some(pos)) => pos - 1,
+                    (Named::ArrowDown, Some(pos)) => pos + 1,
+                    _ => 0,
+                };
+                if let Some(category) = categories.get(next) {
+                    return self.update(Message::Category(category.clone()));
+                }
+            }
+            (Focus::Categories, Named::ArrowRight | Named::Enter | Named::Tab) => {
+                self.focus = Focus::Entries;
+                self.selected_entry = 0;
+                return self.scroll_to_selected();
+            }
+            (Focus::Categories, _) => {}
+            (Focus::Entries, Named::ArrowLeft)
+                if !searching && self.selected_entry % self.entry_columns() == 0 =>
+            {
+                self.focus = Focus::Categories;
+            }
+            (Focus::Entries, Named::Tab) => return self.focus_search(),
+            (Focus::Entries, Named::Enter) => return self.update(Message::SearchSubmit),
+            (Focus::Entries, Named::ArrowUp) if self.selected_entry < self.entry_columns() => {
+                return self.focus_search();
+            }
+            (Focus::Entries, _) => {
+                let last = self.active_entries().len().saturating_sub(1);
+                let selected = self.selected_entry;
+                let columns = self.entry_columns();
+                self.selected_entry = match key {
+                    Named::ArrowUp => selected.saturating_sub(columns),
+                    Named::ArrowDown => selected + columns,
+                    Named::ArrowLeft if columns > 1 => selected.saturating_sub(1),
+                    Named::ArrowRight if columns > 1 => selected + 1,
+                    Named::PageUp => selected.saturating_sub(PAGE_SIZE * columns),
+                    Named::PageDown => selected + PAGE_SIZE * columns,
+                    Named::Home => 0,
+                    Named::End => last,
+                    _ => return Command::none(),
+                }
+                .min(last);
+                return self.scroll_to_selected();
+            }
+        }
+        Command::none()
+    }
+
+    /// Rebuilds the categories which depend on the history from the entries already loaded.
+    fn update_history_categories(&mut self) {
+        let now = history::now();
+        let find = |appid: &str| {
+            self.entry_map
+                .values()
+                .flatten()
+                .find(|entry| entry.appid == appid)
+        };
+        for (category, history_entries) in
+            history_categories(find, &self.history, now, &self.config)
+        {
+            self.entry_map.insert(category.to_string(), history_entries);
+        }
+        let favorites = &self.app_list_config.favorites;
+        for (category, category_entries) in self.entry_map.iter_mut() {
+            let sort_mode = match sort_mode(&self.config, category) {
+                Some(sort_mode) if matches!(category.as_str(), "Recent" | "Frequent") => sort_mode,
+                Some(SortMode::MostUsed) => {
+                    // sort_entries keeps ties in the order it is given
+                    category_entries.sort_by(|a, b| natural_lexical_cmp(&a.name, &b.name));
+                    SortMode::MostUsed
+                }
+                _ => continue,
+            };
+            sort_entries(
+                category_entries,
+                sort_mode,
+                category,
+                favorites,
+                &self.history,
+                now,
+                &self.config,
+            );
+        }
+        if self.config.skip_empty_categories {
+            self.entry_map.retain(|_, v| !v.is_empty());
+        }
+    }
+
+    fn end_reorder(&mut self) {
+        self.dragged_category = None;
+        self.category_drop_target = None;
+        self.dragged_entry = None;
+        self.entry_drop_target = None;
+    }
+
+    fn record_launch(&mut self, appid: &str) {
+        let mut history = self.history.clone();
+        history.record(appid, history::now(), self.config.history_size);
+        match &self.state_handler {
+            Some(state_handler) => {
+                if let Err(err) = self.history.set_launches(state_handler, history.launches) {
+                    eprintln!("failed to save state \"launches\": {}", err);
+                }
+            }
+            None => {
+                self.history = history;
+                eprintln!("failed to save state \"launches\": no state handler");
+            }
+        }
+    }
+
+    /// The icon of `category`, the config can override or disable the default one.
+    fn category_icon(&self, category: &str) -> Option<String> {
+        if let Some(icon) = self.config.category_icons.get(category) {
+            return icon.clone();
+        }
+        self.menu
+            .as_ref()
+            .and_then(|menu| menu.find(category)?.icon.clone())
+            .or_else(|| category::icon_name(category).map(str::to_string))
+    }
+
+    /// The translated name of `category`, submenus of the XDG menu bring their own.
+    fn category_label(&self, category: &str) -> String {
+        self.menu
+            .as_ref()
+            .and_then(|menu| menu.find(category)?.label.clone())
+            .unwrap_or_else(|| category::display_name(category))
+    }
+
+    /// The layout of the entry pane, search results use the global one.
+    ///
+    /// Sub-categories inherit the layout of their main category.
+    fn entry_layout(&self) -> EntryLayout {
+        if self.searching() {
+            return self.config.entry_layout;
+        }
+        let category = &self.active_category;
+        let main = category.split('/').next().unwrap_or_default();
+        self.config
+            .category_layouts
+            .get(category)
+            .or_else(|| self.config.category_layouts.get(main))
+            .copied()
+            .unwrap_or(self.config.entry_layout)
+    }
+
+    /// Whether the entries shown are in the [`SortMode::Manual`] order, search results never are.
+    fn manual_order(&self) -> bool {
+        !self.searching()
+            && sort_mode(&self.config, &self.active_category) == Some(SortMode::Manual)
+    }
+
+    /// The maximum width and height of the popup.
+    fn popup_size(&self) -> (f32, f32) {
+        let mut width = self.config.popup_width as f32;
+        // the grid does not shrink, so the popup has to make room for it
+        let uses_grid = self.config.entry_layout == EntryLayout::Grid
+            || self
+                .config
+                .category_layouts
+                .values()
+                .any(|layout| *layout == EntryLayout::Grid);
+        if uses_grid {
+            let (cell_width, _) = grid_cell_size(&self.config);
+            width = width.max(cell_width * self.config.grid_columns as f32 + 250.0);
+        }
+        let available_size = self.available_size();
+        let mut height = match self.config.popup_height {
+            Some(height) => height as f32,
+            // the popup grows with the output unless its height is configured
+            None => available_size.map_or(DEFAULT_POPUP_HEIGHT, |(_, available_height)| {
+                available_height * POPUP_HEIGHT_FRACTION
+            }),
+        };
+        if let Some((available_width, available_height)) = available_size {
+            width = width.min(available_width);
+            height = height.min(available_height);
+        }
+        (width, height)
+    }
+
+    /// The space the panel leaves on its output, once the size of the output is known.
+    fn available_size(&self) -> Option<(f32, f32)> {
+        let (output_width, output_height) = self.output_size?;
+        let applet = &self.core.applet;
+        let (icon_width, icon_height) = applet.suggested_size(true);
+        let panel = icon_width.max(icon_height) + 2 * applet.suggested_padding(true);
+        let mut width = output_width as f32 - 2.0 * POPUP_MARGIN;
+        let mut height = output_height as f32 - 2.0 * POPUP_MARGIN;
+        match applet.anchor {
+            PanelAnchor::Top | PanelAnchor::Bottom => height -= panel as f32,
+            PanelAnchor::Left | PanelAnchor::Right => width -= panel as f32,
+        }
+        Some((width.max(0.0), height.max(0.0)))
+    }
+
+    /// Entries per row of the entry pane.
+    fn entry_columns(&self) -> usize {
+        match self.entry_layout() {
+            EntryLayout::List => 1,
+            EntryLayout::Grid => self.config.grid_columns.max(1),
+        }
+    }
+
+    fn list_entry_view(&self, entry: &Entry) -> Element<Message> {
+        let Spacing { space_xxs, .. } = self.core.system_theme().cosmic().spacing;
+
+        let mut txt = widget::column::with_capacity(2).push(widget::text(entry.name.clone()));
+        let mut txt_height = Length::Shrink;
+        if self.config.entry_details == EntryDetails::Subtitle {
+            if let Some(subtitle) = entry.subtitle() {
+                txt = txt.push(
+                    widget::text(subtitle.to_string())
+                        .size(12)
+                        .style(cosmic::theme::Text::Custom(dimmed_text)),
+                );
+            }
+            // entries without a subtitle keep the same height, scrolling relies on it
+            txt_height = Length::Fixed(LABEL_LINE_HEIGHT + SUBTITLE_HEIGHT);
+        }
+        let txt = txt
+            .apply(widget::container)
+            .width(Length::Fill)
+            .height(txt_height)
+            .center_y();
+        let icon = entry.icon.as_cosmic_icon().size(20);
+        let mut row = widget::row::with_capacity(3)
+            .push(icon)
+            .push(txt)
+            .spacing(space_xxs)
+            .align_items(Alignment::Center);
+        if self.launching.as_ref() == Some(&entry.appid) {
+            row = row.push(self.spinner(16));
+        }
+        row.into()
+    }
+
+    fn grid_entry_view(&self, entry: &Entry) -> Element<Message> {
+        let Spacing { space_xxs, .. } = self.core.system_theme().cosmic().spacing;
+
+        let icon_size = self.config.grid_icon_size;
+        // the spinner takes the place of the icon, there is no room next to it
+        let icon: Element<Message> = if self.launching.as_ref() == Some(&entry.appid) {
+            self.spinner(icon_size)
+        } else {
+            entry.icon.as_cosmic_icon().size(icon_size).into()
+        };
+        let (cell_width, _) = grid_cell_size(&self.config);
+        let (label, lines) = if self.config.wrap_labels {
+            (entry.name.clone(), 2.0)
+        } else {
+            // roughly the number of characters which fit into a line
+            let max_chars = (cell_width / 8.0) as usize;
+            (shorten(&entry.name, max_chars), 1.0)
+        };
+        let label = widget::text(label)
+            .horizontal_alignment(Horizontal::Center)
+            .width(Length::Fill)
+            .apply(widget::container)
+            .height(Length::Fixed(lines * LABEL_LINE_HEIGHT))
+            .clip(true);
+        widget::column::with_capacity(2)
+            .push(icon)
+            .push(label)
+            .spacing(space_xxs)
+            .align_items(Alignment::Center)
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// `GenericName` and `Comment` for a tooltip, if they are not shown as a subtitle.
+    fn tooltip_details(&self, entry: &Entry, layout: EntryLayout) -> Option<String> {
+        match (self.config.entry_details, layout) {
+            (EntryDetails::Tooltip, _) | (EntryDetails::Subtitle, EntryLayout::Grid) => {
+                entry.details()
+            }
+            _ => None,
+        }
+    }
+
+    /// The busy indicator of the launching entry.
+    fn spinner(&self, size: u16) -> Element<Message> {
+        widget::text(SPINNER_FRAMES[self.spinner_frame])
+            .size(size)
+            .line_height(1.0)
+            .into()
+    }
+
+    fn reset_popup_state(&mut self) {
+        self.search_query.clear();
+        self.context_menu = None;
+        self.launching = None;
+        self.dragged_entry = None;
+        self.entry_drop_target = None;
+    }
+
+    fn context_menu_view(&self, entry: &Entry) -> Element<Message> {
+        let Spacing { space_xxs, .. } = self.core.system_theme().cosmic().spacing;
+
+        let mut menu = widget::column::with_capacity(entry.actions.len() + 6);
+        for action in &entry.actions {
+            let btn = widget::button(widget::text(action.name.clone()))
+                .on_press(Message::SpawnExec(entry.action_launch(action)))
+                .style(cosmic::theme::Button::HeaderBar)
+                .width(Length::Fill);
+            menu = menu.push(btn);
+        }
+        if !entry.actions.is_empty() {
+            menu = menu.push(widget::divider::horizontal::light());
+        }
+        let favorite_label = if self.app_list_config.favorites.contains(&entry.appid) {
+            fl!("remove-favorite")
+        } else {
+            fl!("add-favorite")
+        };
+        let favorite = widget::button(widget::text(favorite_label))
+            .on_press(Message::ToggleFavorite(entry.appid.clone()))
+            .style(cosmic::theme::Button::HeaderBar)
+            .width(Length::Fill);
+        menu = menu.push(favorite);
+        let hide = widget::button(widget::text(fl!("hide")))
+            .on_press(Message::Hide(entry.appid.clone()))
+            .style(cosmic::theme::Button::HeaderBar)
+            .width(Length::Fill);
+        menu = menu.push(hide);
+        if self.manual_order() {
+            menu = menu.push(widget::divider::horizontal::light());
+            for (label, offset) in [(fl!("move-up"), -1), (fl!("move-down"), 1)] {
+                let btn = widget::button(widget::text(label))
+                    .on_press(Message::MoveEntry(entry.appid.clone(), offset))
+                    .style(cosmic::theme::Button::HeaderBar)
+                    .width(Length::Fill);
+                menu = menu.push(btn);
+            }
+        }
+        widget::container(menu)
+            .padding(space_xxs)
+            .width(Length::Fixed(240.0))
+            .style(cosmic::theme::Container::Dropdown)
+            .into()
+    }
+
+    fn focus_search(&mut self) -> Command<cosmic::app::Message<Message>> {
+        self.focus = Focus::Search;
+        text_input::focus(self.search_id.clone())
+    }
+
+    /// Scrolls the right pane so that the selected entry is visible.
+    fn scroll_to_selected(&self) -> Command<cosmic::app::Message<Message>> {
+        let columns = self.entry_columns();
+        let rows = self.active_entries().len().div_ceil(columns);
+        let y = if rows > 1 {
+            (self.selected_entry / columns) as f32 / (rows - 1) as f32
+        } else {
+            0.0
+        };
+        scrollable::snap_to(
+            self.scrollable_id.clone(),
+            scrollable::RelativeOffset { x: 0.0, y },
+        )
+    }
+
+    /// All entries matching the search query, best match first.
+    fn search_results(&self) -> Vec<&Entry> {
+        let query = self.search_query.to_lowercase();
+        let terms: Vec<_> = query.split_whitespace().collect();
+        let mut seen = HashSet::new();
+        let mut results: Vec<_> = self
+            .entry_map
+            .values()
+            .flatten()
+            .filter(|entry| seen.insert(&entry.appid))
+            .filter_map(|entry| entry.search_score(&terms).map(|score| (score, entry)))
+            .collect();
+        results.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then_with(|| natural_lexical_cmp(&a.name, &b.name))
+        });
+        results.into_iter().map(|(_, entry)| entry).collect()
+    }
+}
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Entry {
+    /// The desktop file ID.
+    id: String,
+    path: PathBuf,
     name: String,
-    exec: String,
+    exec: Vec<String>,
     categories: Vec<String>,
+    /// The `Categories` key as is, `categories` only has the configured ones.
+    desktop_categories: Vec<String>,
+    /// When the desktop file was modified, in seconds since the unix epoch.
+    installed: u64,
     icon: IconSource,
     appid: String,
+    generic_name: Option<String>,
+    comment: Option<String>,
+    keywords: Vec<String>,
+    actions: Vec<Action>,
+    terminal: bool,
+    startup_notify: bool,
+    dbus_activatable: bool,
+}
+
+/// A `[Desktop Action ...]` group of an entry.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Action {
+    id: String,
+    name: String,
+    exec: Vec<String>,
+}
+
+impl Entry {
+    fn launch(&self) -> Launch {
+        Launch {
+            appid: self.appid.clone(),
+            name: self.name.clone(),
+            exec: self.exec.clone(),
+            terminal: self.terminal,
+            startup_notify: self.startup_notify,
+            dbus_activatable: self.dbus_activatable,
+            action: None,
+        }
+    }
+
+    fn action_launch(&self, action: &Action) -> Launch {
+        Launch {
+            appid: self.appid.clone(),
+            name: format!("{} – {}", self.name, action.name),
+            exec: action.exec.clone(),
+            terminal: self.terminal,
+            startup_notify: self.startup_notify,
+            dbus_activatable: self.dbus_activatable,
+            action: Some(action.id.clone()),
+        }
+    }
+
+    /// The `GenericName`, or the `Comment` if there is none or it repeats the name.
+    fn subtitle(&self) -> Option<&str> {
+        [self.generic_name.as_deref(), self.comment.as_deref()]
+            .into_iter()
+            .flatten()
+            .find(|subtitle| *subtitle != self.name)
+    }
+
+    /// `GenericName` and `Comment` on separate lines, leaving out what repeats the name.
+    fn details(&self) -> Option<String> {
+        let details: Vec<&str> = [self.generic_name.as_deref(), self.comment.as_deref()]
+            .into_iter()
+            .flatten()
+            .filter(|detail| *detail != self.name)
+            .collect();
+        (!details.is_empty()).then(|| details.join("\n"))
+    }
+
+    /// Every term has to match at least one field, matches on the name weigh most.
+    fn search_score(&self, terms: &[&str]) -> Option<u32> {
+        let mut total = 0;
+        for term in terms {
+            let fields = [(self.name.as_str(), 4), (self.appid.as_str(), 2)]
+                .into_iter()
+                .chain(self.generic_name.as_deref().map(|name| (name, 3)))
+                .chain(self.keywords.iter().map(|keyword| (keyword.as_str(), 2)))
+                .chain(self.comment.as_deref().map(|comment| (comment, 1)));
+            total += fields
+                .filter_map(|(field, weight)| search::score(term, field).map(|s| s * weight))
+                .max()?;
+        }
+        Some(total)
+    }
 }
 
 fn entry_map(
     mut entries: Vec<Entry>,
     favorites: Vec<String>,
+    history: &History,
+    menu: Option<&menu::Tree>,
     config: &Config,
 ) -> HashMap<String, Vec<Entry>> {
     entries.sort_by(|a, b| natural_lexical_cmp(&a.name, &b.name));
-    let mut entry_map = HashMap::with_capacity(entries.len());
-    for entry in &entries {
-        let mut categories: Vec<_> = entry.categories.iter().collect();
-        categories.sort_by(|a, b| category_cmp(a, b));
-        categories.dedup();
-        for category in categories {
-            entry_map
-                .entry(category.clone())
-                .or_insert(Vec::new())
-                .push(entry.clone());
-        }
-    }
+    let mut entry_map = match menu {
+        Some(menu) => menu_entry_map(&entries, menu, config.sub_categories),
+        None => category_entry_map(&entries),
+    };
     // todo only works if entry is present
-    for entry in favorites {
-        if let Some(entry) = entries.iter().find(|it| it.appid == entry) {
+    for entry in &favorites {
+        if let Some(entry) = entries.iter().find(|it| it.appid == *entry) {
             entry_map
                 .entry("Favorites".into())
                 .or_insert(Vec::new())
@@ -444,7 +1663,43 @@ fn entry_map(
         .or_insert(Vec::new())
         .sort_by(|a, b| natural_lexical_cmp(&a.name, &b.name));
 
-    let other = entry_map.get("Other");
+    // unions refer to the categories as grouped so far, so they do not depend on each other
+    let custom_categories: Vec<(&String, Vec<Entry>)> = config
+        .custom_categories
+        .iter()
+        .map(|(name, custom)| {
+            let matcher = custom_category::Matcher::new(name, custom);
+            let in_union = |entry: &Entry| {
+                custom.categories.iter().any(|category| {
+                    entry_map.get(category).map_or(false, |entries| {
+                        entries.iter().any(|e| e.appid == entry.appid)
+                    })
+                })
+            };
+            let custom_entries = entries
+                .iter()
+                .filter(|entry| {
+                    matcher.matches(&entry.appid, &entry.name, &entry.exec.join(" "))
+                        || in_union(entry)
+                })
+                .cloned()
+                .collect();
+            (name, custom_entries)
+        })
+        .collect();
+    // a custom category named like an existing one adds to it
+    for (name, custom_entries) in custom_categories {
+        let category_entries = entry_map.entry(name.clone()).or_insert(Vec::new());
+        for entry in custom_entries {
+            if !category_entries.contains(&entry) {
+                category_entries.push(entry);
+            }
+        }
+        category_entries.sort_by(|a, b| natural_lexical_cmp(&a.name, &b.name));
+    }
+
+    // the entries directly in the root menu are uncategorized on purpose
+    let other = entry_map.get("Other").filter(|_| menu.is_none());
     if let Some(other) = other {
         let other: Vec<_> = other
             .iter()
@@ -461,6 +1716,25 @@ fn entry_map(
             .collect();
         entry_map.insert("Other".to_string(), other);
     }
+    // after Other, the history does not decide whether an entry is uncategorized
+    let now = history::now();
+    let find = |appid: &str| entries.iter().find(|entry| entry.appid == appid);
+    for (category, history_entries) in history_categories(find, history, now, config) {
+        entry_map.insert(category.to_string(), history_entries);
+    }
+    for (category, category_entries) in entry_map.iter_mut() {
+        if let Some(sort_mode) = sort_mode(config, category) {
+            sort_entries(
+                category_entries,
+                sort_mode,
+                category,
+                &favorites,
+                history,
+                now,
+                config,
+            );
+        }
+    }
     if config.skip_empty_categories {
         entry_map.retain(|_, v| !v.is_empty());
     }
@@ -469,65 +1743,530 @@ fn entry_map(
     entry_map
 }
 
+/// The entries of `Recent` and `Frequent` if they are enabled, `find` looks up an appid.
+fn history_categories<'a>(
+    find: impl Fn(&str) -> Option<&'a Entry>,
+    history: &History,
+    now: u64,
+    config: &Config,
+) -> Vec<(&'static str, Vec<Entry>)> {
+    [
+        (
+            "Recent",
+            config.show_recent,
+            history.recent(config.history_size),
+        ),
+        (
+            "Frequent",
+            config.show_frequent,
+            history.frequent(now, config.history_size),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, enabled, _)| *enabled)
+    .map(|(category, _, appids)| {
+        let history_entries = appids.into_iter().filter_map(&find).cloned().collect();
+        (category, history_entries)
+    })
+    .collect()
+}
+
+/// The sort mode of `category`, `None` for the history categories which have their own order.
+///
+/// Sub-categories inherit the sort mode of their main category.
+fn sort_mode(config: &Config, category: &str) -> Option<SortMode> {
+    let main = category.split('/').next().unwrap_or_default();
+    if let Some(sort_mode) = config
+        .category_sort_modes
+        .get(category)
+        .or_else(|| config.category_sort_modes.get(main))
+    {
+        return Some(*sort_mode);
+    }
+    (!matches!(category, "Recent" | "Frequent")).then_some(config.sort_mode)
+}
+
+/// Sorts alphabetically sorted `entries` by `sort_mode`, which keeps ties in alphabetical order.
+fn sort_entries(
+    entries: &mut [Entry],
+    sort_mode: SortMode,
+    category: &str,
+    favorites: &[String],
+    history: &History,
+    now: u64,
+    config: &Config,
+) {
+    let position = |order: &[String], entry: &Entry| {
+        order
+            .iter()
+            .position(|appid| *appid == entry.appid)
+            .unwrap_or(usize::MAX)
+    };
+    match sort_mode {
+        SortMode::Alphabetical => {}
+        SortMode::Favorites => entries.sort_by_key(|entry| position(favorites, entry)),
+        SortMode::MostUsed => entries.sort_by(|a, b| {
+            let a = history.frecency(&a.appid, now);
+            let b = history.frecency(&b.appid, now);
+            b.partial_cmp(&a).unwrap_or(cmp::Ordering::Equal)
+        }),
+        SortMode::RecentlyInstalled => entries.sort_by(|a, b| b.installed.cmp(&a.installed)),
+        SortMode::Manual => {
+            let order = config
+                .manual_order
+                .get(category)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            entries.sort_by_key(|entry| position(order, entry));
+        }
+    }
+}
+
+fn category_entry_map(entries: &[Entry]) -> HashMap<String, Vec<Entry>> {
+    let mut entry_map = HashMap::with_capacity(entries.len());
+    for entry in entries {
+        let mut categories: Vec<_> = entry.categories.iter().collect();
+        categories.sort_by(|a, b| category_cmp(a, b));
+        categories.dedup();
+        for category in categories {
+            entry_map
+                .entry(category.clone())
+                .or_insert(Vec::new())
+                .push(entry.clone());
+        }
+    }
+    entry_map
+}
+
+/// Groups `entries` by the top-level submenus of `menu`, which include the entries of their submenus.
+///
+/// The submenus one level down become sub-categories, deeper ones are part of those.
+fn menu_entry_map(
+    entries: &[Entry],
+    menu: &menu::Tree,
+    sub_categories: bool,
+) -> HashMap<String, Vec<Entry>> {
+    let menu_entries = |menu: &menu::Tree| -> Vec<Entry> {
+        let ids = menu.all_entries();
+        entries
+            .iter()
+            .filter(|entry| ids.contains(entry.id.as_str()))
+            .cloned()
+            .collect()
+    };
+    let mut entry_map = HashMap::with_capacity(menu.submenus.len() + 1);
+    for submenu in &menu.submenus {
+        entry_map.insert(submenu.name.clone(), menu_entries(submenu));
+        if !sub_categories {
+            continue;
+        }
+        for nested in &submenu.submenus {
+            let category = format!("{}/{}", submenu.name, nested.name);
+            entry_map.insert(category, menu_entries(nested));
+        }
+    }
+    let other = entries
+        .iter()
+        .filter(|entry| menu.entries.contains(&entry.id))
+        .cloned()
+        .collect();
+    entry_map.insert("Other".to_string(), other);
+    entry_map
+}
+
+/// Loads `applications.menu` and sorts `entries` into it.
+fn xdg_menu(entries: &[Entry]) -> Option<menu::Tree> {
+    let menu = match menu::load() {
+        Ok(menu) => menu,
+        Err(err) => {
+            eprintln!("failed to load applications.menu: {}", err);
+            return None;
+        }
+    };
+    let menu_entries: Vec<MenuEntry> = entries
+        .iter()
+        .map(|entry| MenuEntry {
+            id: &entry.id,
+            categories: &entry.desktop_categories,
+        })
+        .collect();
+    let locales = freedesktop_desktop_entry::get_languages_from_env();
+    Some(menu.resolve(&menu_entries, &locales))
+}
+
 fn entries(config: &Config) -> Vec<Entry> {
     use freedesktop_desktop_entry::{default_paths, get_languages_from_env, Iter};
     let locales = get_languages_from_env();
+    let desktops = current_desktops();
 
-    Iter::new(default_paths())
-        .filter_map(|p| parse_entry(&p, config, &locales))
-        .collect()
+    let mut ids = HashSet::new();
+    let mut files = HashSet::new();
+    let mut duplicates = HashSet::new();
+    let mut entries = Vec::new();
+    // `default_paths` lists the user directory first, followed by `$XDG_DATA_DIRS` by precedence
+    for dir in default_paths() {
+        for path in Iter::new(vec![dir.clone()]) {
+            let Some(id) = desktop_file_id(&dir, &path) else {
+                continue;
+            };
+            // the first file with an id shadows all later ones, even if it is hidden
+            if !ids.insert(id.clone()) {
+                continue;
+            }
+            // the same file can be reachable through symlinks or repeated data dirs
+            if let Ok(canonical) = path.canonicalize() {
+                if !files.insert(canonical) {
+                    continue;
+                }
+            }
+            let Some(entry) = parse_entry(&path, id, config, &locales, &desktops) else {
+                continue;
+            };
+            // entries without `Exec` are only activated over D-Bus, by their distinct appid
+            if entry.exec.is_empty() || duplicates.insert((entry.name.clone(), entry.exec.clone()))
+            {
+                entries.push(entry);
+            }
+        }
+    }
+    entries
+}
+
+/// The path relative to the `applications` directory with `/` replaced by `-`.
+fn desktop_file_id(dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir).ok()?;
+    Some(relative.to_str()?.replace('/', "-"))
+}
+
+/// The desktop names from `$XDG_CURRENT_DESKTOP`, used for `OnlyShowIn` and `NotShowIn`.
+fn current_desktops() -> Vec<String> {
+    std::env::var("XDG_CURRENT_DESKTOP")
+        .map(|desktops| {
+            desktops
+                .split(':')
+                .filter(|desktop| !desktop.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether the entry should be listed according to the Desktop Entry Specification.
+fn is_visible(desktop_entry: &DesktopEntry, desktops: &[String]) -> bool {
+    let is_true = |key| desktop_entry.desktop_entry(key) == Some("true");
+    let contains_current_desktop = |key| {
+        desktop_entry.desktop_entry(key).map_or(false, |list| {
+            list.split_terminator(';').any(|desktop| {
+                desktops
+                    .iter()
+                    .any(|current| current.eq_ignore_ascii_case(desktop))
+            })
+        })
+    };
+    if desktop_entry.no_display() || is_true("Hidden") {
+        return false;
+    }
+    if desktop_entry.desktop_entry("OnlyShowIn").is_some()
+        && !contains_current_desktop("OnlyShowIn")
+    {
+        return false;
+    }
+    if contains_current_desktop("NotShowIn") {
+        return false;
+    }
+    desktop_entry
+        .desktop_entry("TryExec")
+        .map_or(true, |try_exec| launch::find_in_path(try_exec).is_some())
+}
+
+fn dimmed_text(theme: &Theme) -> text::Appearance {
+    let mut color: iced::Color = theme.cosmic().on_bg_color().into();
+    color.a *= 0.7;
+    text::Appearance { color: Some(color) }
+}
+
+/// The width and height of a cell of [`EntryLayout::Grid`].
+fn grid_cell_size(config: &Config) -> (f32, f32) {
+    let icon_size = config.grid_icon_size as f32;
+    let lines = if config.wrap_labels { 2.0 } else { 1.0 };
+    // the padding of the button and the spacing between icon and name
+    let width = (icon_size + 48.0).max(96.0);
+    let height = icon_size + lines * LABEL_LINE_HEIGHT + 20.0;
+    (width, height)
+}
+
+/// Cuts `name` to `max_chars` graphemes, ending with an ellipsis if anything was cut.
+fn shorten(name: &str, max_chars: usize) -> String {
+    let graphemes: Vec<&str> = name.graphemes(true).collect();
+    if graphemes.len() <= max_chars {
+        return name.to_string();
+    }
+    let mut shortened: String = graphemes[..max_chars.saturating_sub(1)].concat();
+    shortened.push('…');
+    shortened
+}
+
+fn focus_ring(theme: &Theme) -> container::Appearance {
+    let cosmic = theme.cosmic();
+    container::Appearance {
+        border: Border {
+            color: cosmic.accent_color().into(),
+            width: 2.0,
+            radius: cosmic.corner_radii.radius_s.into(),
+        },
+        ..Default::default()
+    }
+}
+
+/// Adds the enabled history categories after Favorites, custom categories before Other
+/// and applies `sort_categories`.
+///
+/// Returns the added categories, so they can be told apart from the configured ones.
+fn prepare_categories(config: &mut Config) -> Vec<String> {
+    let mut added = Vec::new();
+    let history_categories = [
+        ("Recent", config.show_recent),
+        ("Frequent", config.show_frequent),
+    ];
+    for (category, enabled) in history_categories {
+        let present = config.categories.iter().any(|c| c == category);
+        if enabled && !present {
+            let pos = config
+                .categories
+                .iter()
+                .position(|c| c == "Favorites")
+                .map_or(0, |pos| pos + 1);
+            let pos = if category == "Frequent" {
+                config
+                    .categories
+                    .iter()
+                    .position(|c| c == "Recent")
+                    .map_or(pos, |pos| pos + 1)
+            } else {
+                pos
+            };
+            config.categories.insert(pos, category.to_string());
+            added.push(category.to_string());
+        } else if !enabled && present {
+            config.categories.retain(|c| c != category);
+        }
+    }
+    let mut custom_categories: Vec<String> = config
+        .custom_categories
+        .keys()
+        .filter(|name| !config.categories.contains(name))
+        .cloned()
+        .collect();
+    custom_categories.sort_by(|a, b| natural_lexical_cmp(a, b));
+    let pos = config
+        .categories
+        .iter()
+        .position(|c| c == "Other")
+        .unwrap_or(config.categories.len());
+    added.extend(custom_categories.iter().cloned());
+    config.categories.splice(pos..pos, custom_categories);
+    if config.sort_categories {
+        config.categories.sort_by(|a, b| category_cmp(a, b));
+    }
+    added
 }
 
 fn category_cmp(a: &str, b: &str) -> cmp::Ordering {
-    // favorites top - other bottom
+    // favorites, recent and frequent top - other bottom
     return match (a, b) {
-        ("Favorites", "Favorites") | ("Other", "Other") => cmp::Ordering::Equal,
+        ("Favorites", "Favorites")
+        | ("Recent", "Recent")
+        | ("Frequent", "Frequent")
+        | ("Other", "Other") => cmp::Ordering::Equal,
         ("Favorites", _) => cmp::Ordering::Less,
         (_, "Favorites") => cmp::Ordering::Greater,
+        ("Recent", _) => cmp::Ordering::Less,
+        (_, "Recent") => cmp::Ordering::Greater,
+        ("Frequent", _) => cmp::Ordering::Less,
+        (_, "Frequent") => cmp::Ordering::Greater,
         ("Other", _) => cmp::Ordering::Greater,
         (_, "Other") => cmp::Ordering::Less,
-        _ => natural_lexical_cmp(a, b),
+        _ => natural_lexical_cmp(&category::display_name(a), &category::display_name(b)),
     };
 }
 
-fn parse_entry(path: &Path, config: &Config, locales: &[String]) -> Option<Entry> {
+fn parse_entry(
+    path: &Path,
+    id: String,
+    config: &Config,
+    locales: &[String],
+    desktops: &[String],
+) -> Option<Entry> {
     let bytes = fs::read_to_string(path).ok()?;
     let desktop_entry = DesktopEntry::from_str(path, &bytes, locales).ok()?;
 
-    (!desktop_entry.no_display()).then_some(())?;
+    (config.show_hidden || is_visible(&desktop_entry, desktops)).then_some(())?;
+
+    let entry_override = config.overrides.get(&*desktop_entry.appid);
+    (config.show_hidden || !entry_override.map_or(false, |o| o.hide)).then_some(())?;
 
-    let name = desktop_entry
-        .name(locales)
-        .unwrap_or_else(|| Cow::from(&*desktop_entry.appid))
-        .to_string();
+    let name = entry_override
+        .and_then(|o| o.name.clone())
+        .unwrap_or_else(|| {
+            desktop_entry
+                .name(locales)
+                .unwrap_or_else(|| Cow::from(&*desktop_entry.appid))
+                .to_string()
+        });
 
-    let exec = desktop_entry.exec()?.to_string();
+    // the desktop file changes rarely after the installation
+    let installed = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_secs());
+    let terminal = desktop_entry.terminal();
+    // unless an entry opts out explicitly, it gets an activation token
+    let startup_notify = desktop_entry.desktop_entry("StartupNotify") != Some("false");
+    let dbus_activatable = desktop_entry.desktop_entry("DBusActivatable") == Some("true");
 
-    let icon = desktop_entry.icon().unwrap_or(&desktop_entry.appid);
+    let field_codes = FieldCodes {
+        icon: desktop_entry.icon(),
+        name: &name,
+        path,
+    };
+    // `Exec` is optional for D-Bus activation, it is only the fallback then
+    let exec = match desktop_entry
+        .exec()
+        .map(|exec| exec::parse(exec, &field_codes))
+    {
+        Some(Ok(exec)) => exec,
+        Some(Err(err)) => {
+            eprintln!("malformed Exec key in {}: {}", path.display(), err);
+            dbus_activatable.then_some(())?;
+            Vec::new()
+        }
+        None => {
+            dbus_activatable.then_some(())?;
+            Vec::new()
+        }
+    };
+
+    let icon = entry_override
+        .and_then(|o| o.icon.as_deref())
+        .or(desktop_entry.icon())
+        .unwrap_or(&desktop_entry.appid);
     let icon = IconSource::from_unknown(icon);
     let appid = desktop_entry.appid.to_string();
-    let mut categories = Vec::new();
-    for mut category in desktop_entry.categories()?.split_terminator(";") {
-        let category_lowercase = category.to_lowercase();
-        category = if let Some(config_category) = config
+    let generic_name = desktop_entry.generic_name(locales).map(|s| s.to_string());
+    let comment = desktop_entry.comment(locales).map(|s| s.to_string());
+    let keywords = desktop_entry
+        .desktop_entry_localized("Keywords", locales)
+        .map(|keywords| {
+            keywords
+                .split_terminator(';')
+                .filter(|keyword| !keyword.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    let actions = desktop_entry
+        .actions()
+        .map(|actions| {
+            actions
+                .split_terminator(';')
+                .filter_map(|action| {
+                    let name = desktop_entry.action_name(action, locales)?.to_string();
+                    let exec = match desktop_entry.action_exec(action) {
+                        Some(exec) => match exec::parse(exec, &field_codes) {
+                            Ok(exec) => exec,
+                            Err(err) => {
+                                eprintln!(
+                                    "malformed Exec key of action {} in {}: {}",
+                                    action,
+                                    path.display(),
+                                    err
+                                );
+                                dbus_activatable.then_some(())?;
+                                Vec::new()
+                            }
+                        },
+                        None => {
+                            dbus_activatable.then_some(())?;
+                            Vec::new()
+                        }
+                    };
+                    Some(Action {
+                        id: action.to_string(),
+                        name,
+                        exec,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let desktop_categories: Vec<String> = match entry_override.and_then(|o| o.categories.clone()) {
+        Some(categories) => categories,
+        None => desktop_entry
+            .categories()?
+            .split_terminator(';')
+            .map(str::to_string)
+            .collect(),
+    };
+    let config_category = |category: &str| {
+        config
             .categories
             .iter()
-            .find(|c| c.to_lowercase() == category_lowercase.as_str())
-        {
-            &config_category
-        } else {
-            "Other"
-        };
-        categories.push(category.to_string());
+            .find(|c| c.eq_ignore_ascii_case(category))
+    };
+    let mut categories = Vec::new();
+    let mut sub_categories = Vec::new();
+    for category in &desktop_categories {
+        if let Some(config_category) = config_category(category) {
+            categories.push(config_category.clone());
+            continue;
+        }
+        // additional categories belong to the related main categories the entry lists,
+        // or to all related ones if it lists none of them
+        let related: Vec<&String> = category::main_categories(category)
+            .iter()
+            .filter_map(|main| config_category(main))
+            .collect();
+        let listed: Vec<&String> = related
+            .iter()
+            .copied()
+            .filter(|main| {
+                desktop_categories
+                    .iter()
+                    .any(|c| c.eq_ignore_ascii_case(main))
+            })
+            .collect();
+        let main_categories = if listed.is_empty() { related } else { listed };
+        if main_categories.is_empty() {
+            categories.push("Other".to_string());
+        }
+        for main in main_categories {
+            categories.push(main.clone());
+            if config.sub_categories {
+                sub_categories.push(format!("{main}/{category}"));
+            }
+        }
     }
     (!categories.is_empty()).then_some(())?;
+    categories.extend(sub_categories);
 
     let entry = Entry {
+        id,
+        path: path.to_path_buf(),
         appid,
         name,
         categories,
+        desktop_categories,
+        installed,
         exec,
         icon,
+        generic_name,
+        comment,
+        keywords,
+        actions,
+        terminal,
+        startup_notify,
+        dbus_activatable,
     };
     Some(entry)
 }